@@ -1,6 +1,8 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 use std::hint::black_box;
 
+use worley_biomes::biome_picker::BiomePicker;
+use worley_biomes::hash::HashVersion;
 use worley_biomes::prelude::*;
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -47,11 +49,50 @@ fn heavy_k_post_calculation(
 }
 
 fn default_worley() -> Worley<BiomeType, SimpleBiomePicker<BiomeType>> {
-    let mut worley: Worley<BiomeType, SimpleBiomePicker<BiomeType>> = Worley::default();
-    worley.zoom = 62.0;
-    worley.sharpness = 20.0;
-    worley.k = 3;
-    worley
+    Worley::builder()
+        .zoom(62.0)
+        .sharpness(20.0)
+        .k(3)
+        .build()
+        .expect("valid worley")
+}
+
+// (biome, weight) for (count) biomes with uneven odds
+fn uneven_weights(count: u16) -> Vec<(BiomeId, f32)> {
+    let sum: f32 = (1..=count).map(|i| i as f32).sum();
+    (0..count)
+        .map(|i| (BiomeId(i), (i + 1) as f32 / sum))
+        .collect()
+}
+
+// 9 picks per sample, like Worley::get()
+#[inline]
+fn pick_32x32<Picker: BiomePicker<BiomeId>>(picker: &Picker) {
+    for z in 0..32 {
+        for x in 0..32 {
+            for _ in 0..9 {
+                let _ = black_box(picker.pick_biome_versioned(0, x, z, HashVersion::V1));
+            }
+        }
+    }
+}
+
+fn weighted_pick_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("weighted pick 32x32");
+    for count in [4, 16, 256] {
+        let weights = uneven_weights(count);
+        let weighted = SimpleBiomePicker::Weighted(weights.clone());
+        let alias = AliasBiomePicker::new(weights).expect("valid weights");
+        group.bench_with_input(
+            BenchmarkId::new("weighted", count),
+            &weighted,
+            |b, picker| b.iter(|| pick_32x32(black_box(picker))),
+        );
+        group.bench_with_input(BenchmarkId::new("alias", count), &alias, |b, picker| {
+            b.iter(|| pick_32x32(black_box(picker)))
+        });
+    }
+    group.finish();
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -77,6 +118,16 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("32x32 sample: surpass tinyvec", |b| {
         b.iter(|| sample_32x32(black_box(&worley_k_8)));
     });
+    let cached_worley = CachedWorley::new(default_worley(), CacheSettings::default());
+    c.bench_function("32x32 sample: cached", |b| {
+        b.iter(|| {
+            for z in 0..32 {
+                for x in 0..32 {
+                    let _ = black_box(&cached_worley).get(x as f64, z as f64);
+                }
+            }
+        });
+    });
     c.bench_function("heavy k post calculation", |b| {
         b.iter_with_setup(
             || {
@@ -91,5 +142,5 @@ fn criterion_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, criterion_benchmark);
+criterion_group!(benches, criterion_benchmark, weighted_pick_benchmark);
 criterion_main!(benches);
@@ -7,8 +7,12 @@ use bracket_fast_noise::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use worley_biomes::{
+    bevy::chunks::{
+        ChunkBiomes, ChunkGenerated, ChunkLoader, ChunkMap, ChunkPlugin, ChunkSettings, chunk_at,
+    },
     bevy::debug_plugin::{DebugColor, DebugPluginSettings, GetWorley, WorleyImage},
     biome_picker::{BiomeVariants, SimpleBiomePicker},
+    blend::BiomeBlend,
     distance_fn::DistanceFn,
     worley::Worley,
 };
@@ -69,9 +73,26 @@ fn main() {
                 spawn_preview_image: true,
                 show_preview_image: true,
                 show_inspector_ui: true,
+                // sampled in the background, a coarse pass shows up first
+                preview_resolution: 256,
+                preview_extent: GRID_SIZE as f64,
             },
             ..default()
         })
+        // samples chunks around the ChunkLoader in the background, instead of every voxel on the main thread
+        .add_plugins(
+            ChunkPlugin::<WorleyHolder, BiomeType, SimpleBiomePicker<BiomeType>> {
+                settings: ChunkSettings {
+                    chunk_size: CHUNK_SIZE as f64,
+                    // one sample per voxel
+                    resolution: CHUNK_SIZE as usize,
+                    load_distance: 3,
+                    unload_distance: 4,
+                    ..default()
+                },
+                ..default()
+            },
+        )
         .insert_resource(VoxelMaterials(HashMap::new()))
         .insert_resource(Offset { x: 0.0, z: 0.0 })
         .add_systems(Startup, setup)
@@ -105,6 +126,8 @@ pub struct VoxelMaterials(HashMap<(u8, u8, u8), Handle<StandardMaterial>>);
 
 ///! how many voxels to generate
 pub const GRID_SIZE: i32 = 32 * 4;
+///! world size of a chunk sampled by the ChunkPlugin
+pub const CHUNK_SIZE: i32 = 32;
 
 ///! store the voxel x,z pos to later find the correct voxel to update
 #[derive(Component)]
@@ -153,9 +176,11 @@ fn setup_voxels(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
 #[derive(Component)]
 pub struct TargetHeight(f32);
 
-///! fetch worley data to UPDATE the voxel height + material
+///! read the sampled chunks to UPDATE the voxel height + material
 fn update_voxel_from_worley(
-    worley_holder: Res<WorleyHolder>,
+    mut generated: MessageReader<ChunkGenerated>,
+    chunk_map: Res<ChunkMap>,
+    chunks: Query<&ChunkBiomes<BiomeType>>,
     mut voxels: Query<(
         &VoxelCoord,
         &mut MeshMaterial3d<StandardMaterial>,
@@ -165,31 +190,30 @@ fn update_voxel_from_worley(
     mut voxel_materials: ResMut<VoxelMaterials>,
     offset: Res<Offset>,
 ) {
-    if !worley_holder.is_changed() {
+    // new chunk samples, or we moved
+    if generated.is_empty() && !offset.is_changed() {
         return;
     }
-
-    let worley = &worley_holder.worley;
+    generated.clear();
 
     for (coord, mut mat, mut target_height) in voxels.iter_mut() {
-        let gx = coord.gx;
-        let gz = coord.gz;
-        let weights = worley.get(gx as f64 + offset.x, gz as f64 + offset.z);
-        // blend colors
-        let mut r = 0.0;
-        let mut g = 0.0;
-        let mut b = 0.0;
-        let mut height = 0.0;
-        let mut wsum = 0.0;
-        for (w, biome) in &weights {
-            let c = biome.get_color();
-            r += c.red as f64 * w;
-            g += c.green as f64 * w;
-            b += c.blue as f64 * w;
-            height += biome.height() * *w as f32;
-            wsum += w;
-        }
-        let color = Srgba::new(r as f32, g as f32, b as f32, 1.0);
+        let x = coord.gx + offset.x.floor() as i32;
+        let z = coord.gz + offset.z.floor() as i32;
+        let chunk = chunk_at(x as f64, z as f64, CHUNK_SIZE as f64);
+        // not sampled yet, keep the old look
+        let Some(biomes) = chunk_map
+            .get(chunk)
+            .and_then(|entity| chunks.get(entity).ok())
+        else {
+            continue;
+        };
+        let local_x = (x - chunk.x * CHUNK_SIZE) as usize;
+        let local_z = (z - chunk.y * CHUNK_SIZE) as usize;
+        let weights = biomes.0.get(local_x, local_z);
+        // blend colors + height
+        let (color, height) = weights
+            .blend(|biome| (biome.get_color(), biome.height()))
+            .unwrap_or((Srgba::BLACK, 0.0));
         let (color, key) = quantize_srgba(color, 32);
         let color_material = voxel_materials
             .0
@@ -222,7 +246,7 @@ fn move_input(
     keyboard: Res<ButtonInput<KeyCode>>,
     mut offset: ResMut<Offset>,
     time: Res<Time>,
-    mut worley_holder: ResMut<WorleyHolder>,
+    mut chunk_loader: Single<&mut Transform, With<ChunkLoader>>,
     mut worley_image: Option<ResMut<WorleyImage>>,
 ) {
     let speed = 32.0;
@@ -245,7 +269,8 @@ fn move_input(
         changed = true;
     }
     if changed {
-        worley_holder.set_changed();
+        // chunks are loaded around the middle of the voxel grid
+        chunk_loader.translation = loader_position(&offset);
         if let Some(worley_image) = &mut worley_image {
             worley_image.preview_offset.0 = offset.x;
             worley_image.preview_offset.1 = offset.z;
@@ -253,23 +278,35 @@ fn move_input(
     }
 }
 
+fn loader_position(offset: &Offset) -> Vec3 {
+    let half = GRID_SIZE as f64 / 2.0;
+    Vec3::new((offset.x + half) as f32, 0.0, (offset.z + half) as f32)
+}
+
 fn setup(mut commands: Commands) {
     // SETUP OUR WORLEY VALUES
-    let mut worley: Worley<BiomeType, SimpleBiomePicker<BiomeType>> = Worley::default();
-    worley.zoom = 62.0;
-    worley.set_distance_fn(DistanceFn::Chebyshev);
-    worley.biome_picker = SimpleBiomePicker::Any;
-    worley.sharpness = 20.0;
-    worley.k = 3;
-    worley.warp_settings.strength = 0.6;
-    worley.warp_settings.noise.set_seed(0);
-    worley.warp_settings.noise.frequency = 0.7;
-    worley.warp_settings.noise.fractal_lacunarity = 2.0;
-    worley.warp_settings.noise.set_fractal_gain(0.6);
-    worley.warp_settings.noise.fractal_octaves = 3;
-    worley.warp_settings.noise.noise_type = NoiseType::PerlinFractal;
-    worley.warp_settings.noise.fractal_type = FractalType::FBM;
+    let worley: Worley<BiomeType, SimpleBiomePicker<BiomeType>> = Worley::builder()
+        .zoom(62.0)
+        .distance_fn(DistanceFn::Chebyshev)
+        .biome_picker(SimpleBiomePicker::Any)
+        .sharpness(20.0)
+        .k(3)
+        .warp_strength(0.6)
+        .warp_noise(FastNoise::default())
+        .noise_seed(0)
+        .noise_frequency(0.7)
+        .fractal_lacunarity(2.0)
+        .fractal_gain(0.6)
+        .fractal_octaves(3)
+        .noise_type(NoiseType::PerlinFractal)
+        .fractal_type(FractalType::FBM)
+        .build()
+        .expect("valid worley");
     commands.insert_resource(WorleyHolder { worley });
+    commands.spawn((
+        ChunkLoader,
+        Transform::from_translation(loader_position(&Offset { x: 0.0, z: 0.0 })),
+    ));
 
     commands.spawn((
         DirectionalLight { ..default() },
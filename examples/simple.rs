@@ -9,6 +9,7 @@ use bracket_fast_noise::prelude::*;
 use serde::{Deserialize, Serialize};
 use worley_biomes::{
     biome_picker::{BiomeVariants, SimpleBiomePicker},
+    blend::BiomeBlend,
     distance_fn::DistanceFn,
     worley::Worley,
 };
@@ -50,21 +51,24 @@ fn main() {
 pub const GRID_SIZE: i32 = 32 * 4;
 
 fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
-    let mut worley: Worley<BiomeType, SimpleBiomePicker<BiomeType>> = Worley::default();
-    worley.zoom = 22.0;
-    worley.seed = 12345;
-    worley.set_distance_fn(DistanceFn::Chebyshev);
-    worley.biome_picker = SimpleBiomePicker::Any;
-    worley.sharpness = 20.0;
-    worley.k = 3;
-    worley.warp_settings.strength = 0.6;
-    worley.warp_settings.noise.set_seed(0);
-    worley.warp_settings.noise.frequency = 0.7;
-    worley.warp_settings.noise.fractal_lacunarity = 2.0;
-    worley.warp_settings.noise.set_fractal_gain(0.6);
-    worley.warp_settings.noise.fractal_octaves = 3;
-    worley.warp_settings.noise.noise_type = NoiseType::PerlinFractal;
-    worley.warp_settings.noise.fractal_type = FractalType::FBM;
+    let worley: Worley<BiomeType, SimpleBiomePicker<BiomeType>> = Worley::builder()
+        .zoom(22.0)
+        .seed(12345)
+        .distance_fn(DistanceFn::Chebyshev)
+        .biome_picker(SimpleBiomePicker::Any)
+        .sharpness(20.0)
+        .k(3)
+        .warp_strength(0.6)
+        .warp_noise(FastNoise::default())
+        .noise_seed(0)
+        .noise_frequency(0.7)
+        .fractal_lacunarity(2.0)
+        .fractal_gain(0.6)
+        .fractal_octaves(3)
+        .noise_type(NoiseType::PerlinFractal)
+        .fractal_type(FractalType::FBM)
+        .build()
+        .expect("valid worley");
 
     let mut img_data = Vec::new();
     for gx in 0..GRID_SIZE {
@@ -72,19 +76,9 @@ fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
             let weights = worley.get(gx as f64, gz as f64);
 
             // blend colors
-            let mut r = 0.0;
-            let mut g = 0.0;
-            let mut b = 0.0;
-            let mut wsum = 0.0;
-            for (w, biome) in &weights {
-                let c = biome_color(*biome);
-                r += c.red as f64 * w;
-                g += c.green as f64 * w;
-                b += c.blue as f64 * w;
-                wsum += w;
-            }
-
-            let color = Srgba::new(r as f32, g as f32, b as f32, 1.0);
+            let color = weights
+                .blend(|biome| biome_color(*biome))
+                .unwrap_or(Srgba::BLACK);
             img_data.push((color.red * 255.0) as u8);
             img_data.push((color.green * 255.0) as u8);
             img_data.push((color.blue * 255.0) as u8);
@@ -0,0 +1,131 @@
+#ifndef WORLEY_BIOMES_H
+#define WORLEY_BIOMES_H
+
+/* generated by cbindgen from src/ffi.rs, don't edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// most weights a sample can have
+#define WB_MAX_K 9
+
+// written for a missing biome: dominant of an empty sample, unused region slots
+#define WB_NO_BIOME UINT16_MAX
+
+// a Worley with a SimpleBiomePicker, created by wb_worley_from_ron()
+typedef struct WbWorley WbWorley;
+
+// one biome of a sample
+typedef struct WbBiomeWeight {
+  double weight;
+  uint16_t biome;
+} WbBiomeWeight;
+
+
+
+
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// create a worley from a .worley.ron config (any version worley_biomes can load).
+// returns NULL on failure, with a message written to (error) when it isn't NULL.
+//
+// # Safety
+// (biome_names) points to (biome_count) NUL terminated strings, (config_ron) is
+// NUL terminated, (error) is NULL or writable for (error_len) bytes
+struct WbWorley *wb_worley_from_ron(const char *const *biome_names,
+                                    size_t biome_count,
+                                    const char *config_ron,
+                                    char *error,
+                                    size_t error_len);
+
+// # Safety
+// (worley) is NULL or from wb_worley_from_ron(), and not used after this
+void wb_worley_free(struct WbWorley *worley);
+
+// weights at (x, z) in the order of Worley::get(), returns how many were written
+// (at most WB_MAX_K and out_len).
+//
+// # Safety
+// (worley) is valid, (out) is writable for (out_len) weights
+size_t wb_worley_sample(const struct WbWorley *worley,
+                        double x,
+                        double z,
+                        struct WbBiomeWeight *out,
+                        size_t out_len);
+
+// strongest biome at (x, z), WB_NO_BIOME if there is none
+//
+// # Safety
+// (worley) is valid
+uint16_t wb_worley_dominant(const struct WbWorley *worley, double x, double z);
+
+// one biome per block, see Worley::pick_dithered(). WB_NO_BIOME if there is none
+//
+// # Safety
+// (worley) is valid
+uint16_t wb_worley_pick_dithered(const struct WbWorley *worley, double x, double z);
+
+// sample a (width) * (depth) grid covering min -> max (max exclusive), see Worley::sample_region().
+// sample (x, z) is written to out[(z * width + x) * stride ..], (stride) weights per sample,
+// unused slots get weight 0.0 and WB_NO_BIOME. a (stride) below the worley's k keeps only the
+// first (stride) weights of Worley::get(): the strongest with AlgorithmVersion::V1, any with V0.
+// returns 0, or -1 when (stride) is 0 or (out_len) is smaller than width * depth * stride
+//
+// # Safety
+// (worley) is valid, (out) is writable for (out_len) weights
+int32_t wb_worley_sample_region(const struct WbWorley *worley,
+                                double min_x,
+                                double min_z,
+                                double max_x,
+                                double max_z,
+                                size_t width,
+                                size_t depth,
+                                size_t stride,
+                                struct WbBiomeWeight *out,
+                                size_t out_len);
+
+// strongest biome of every sample of a (width) * (depth) grid, row major,
+// same grid as wb_worley_sample_region(). returns 0, or -1 when (out_len) < width * depth
+//
+// # Safety
+// (worley) is valid, (out) is writable for (out_len) ids
+int32_t wb_worley_dominant_region(const struct WbWorley *worley,
+                                  double min_x,
+                                  double min_z,
+                                  double max_x,
+                                  double max_z,
+                                  size_t width,
+                                  size_t depth,
+                                  uint16_t *out,
+                                  size_t out_len);
+
+// how many biomes the worley knows
+//
+// # Safety
+// (worley) is valid
+size_t wb_biome_count(const struct WbWorley *worley);
+
+// id of a biome name, WB_NO_BIOME if it isn't known
+//
+// # Safety
+// (worley) is valid, (name) is NUL terminated
+uint16_t wb_biome_id(const struct WbWorley *worley, const char *name);
+
+// writes the NUL terminated name of (biome) to (out), truncated to (out_len) bytes.
+// returns the length of the full name like snprintf, 0 for an unknown biome
+//
+// # Safety
+// (worley) is valid, (out) is NULL or writable for (out_len) bytes
+size_t wb_biome_name(const struct WbWorley *worley, uint16_t biome, char *out, size_t out_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* WORLEY_BIOMES_H */
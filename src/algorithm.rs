@@ -0,0 +1,86 @@
+#[cfg(feature = "bevy")]
+use bevy::reflect::Reflect;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::hash::HashVersion;
+use crate::math::powf;
+
+/// everything that decides the output of a seed: cell hashing, feature point placement,
+/// neighbor selection and weighting. a version never changes once released,
+/// changes to the algorithm become a new version so saved worlds keep their shape.
+/// saved in configs, see config.rs
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum AlgorithmVersion {
+    // the original output: HashVersion::V0, k nearest in an unspecified order
+    #[default]
+    V0,
+    // HashVersion::V1, k nearest sorted closest first
+    V1,
+}
+
+impl AlgorithmVersion {
+    /// every released version, oldest first
+    pub const ALL: [AlgorithmVersion; 2] = [AlgorithmVersion::V0, AlgorithmVersion::V1];
+
+    pub fn hash_version(&self) -> HashVersion {
+        match self {
+            AlgorithmVersion::V0 => HashVersion::V0,
+            AlgorithmVersion::V1 => HashVersion::V1,
+        }
+    }
+
+    /// feature point of a cell, in cell space
+    #[inline(always)]
+    pub(crate) fn cell_point(&self, seed: u64, cell_x: i32, cell_z: i32) -> (f64, f64) {
+        match self {
+            AlgorithmVersion::V0 | AlgorithmVersion::V1 => {
+                let hash = self.hash_version();
+                let h1 = hash.hash(seed.wrapping_add(CELL_SALT_X), cell_x, cell_z);
+                let h2 = hash.hash(seed.wrapping_add(CELL_SALT_Z), cell_x, cell_z);
+                let fx = cell_x as f64 + ((h1 & 0xFFFF) as f64 / 65535.0);
+                let fz = cell_z as f64 + ((h2 & 0xFFFF) as f64 / 65535.0);
+                (fx, fz)
+            }
+        }
+    }
+
+    /// move the k closest (distance, biome) candidates to the front
+    #[inline(always)]
+    pub(crate) fn select_nearest<BiomeT>(&self, candidates: &mut [(f64, BiomeT)], k: usize) {
+        match self {
+            AlgorithmVersion::V0 => {
+                // the order of the k nearest depends on std's select_nth_unstable
+                if k < candidates.len() {
+                    candidates.select_nth_unstable_by(k, |a, b| a.0.total_cmp(&b.0));
+                }
+            }
+            AlgorithmVersion::V1 => {
+                // stable, equal distances keep their neighbor order
+                candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+            }
+        }
+    }
+
+    /// unnormalized weight of a candidate at (distance)
+    #[inline(always)]
+    pub(crate) fn weight(&self, distance: f64, sharpness: f64) -> f64 {
+        match self {
+            AlgorithmVersion::V0 | AlgorithmVersion::V1 => {
+                // very close, high value
+                if distance < 1e-9 {
+                    100.0
+                } else {
+                    // closer to 0, higher weight value
+                    1.0 / powf(distance, sharpness)
+                }
+            }
+        }
+    }
+}
+
+// salts of the feature point hashes, part of V0 and V1 output
+const CELL_SALT_X: u64 = 1337;
+const CELL_SALT_Z: u64 = 7331;
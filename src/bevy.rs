@@ -1 +1,23 @@
+use crate::{
+    biome_picker::{BiomePicker, BiomeVariants},
+    worley::Worley,
+};
+
+pub mod chunks;
+#[cfg(feature = "serde")]
+pub mod config_asset;
+#[cfg(feature = "bevy_debug")]
 pub mod debug_plugin;
+pub mod reflect_plugin;
+pub mod runtime_plugin;
+pub mod splat_image;
+
+///! required for the plugins to find what worley to sample and visualize
+pub trait GetWorley<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    fn get_worley<'a>(&'a self) -> &'a Worley<BiomeT, Picker>;
+    fn get_worley_mut<'a>(&'a mut self) -> &'a mut Worley<BiomeT, Picker>;
+}
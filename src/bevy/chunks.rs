@@ -0,0 +1,328 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, futures::check_ready};
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::{
+    biome_picker::{BiomePicker, BiomeVariants},
+    error::WorleyError,
+    region::{Region, SampleGrid},
+    worley::Worley,
+};
+
+use super::GetWorley;
+
+/// samples chunks around ChunkLoader entities on the AsyncComputeTaskPool, so the frame never
+/// waits for the worley. chunks are entities with a Chunk component, ChunkBiomes is inserted
+/// (and ChunkGenerated sent) when the samples are ready.
+/// chunks closest to a loader are sampled first, chunks that leave the range are despawned
+/// which cancels their task. every chunk is resampled when the worley changes
+pub struct ChunkPlugin<WorleyResT, BiomeT, Picker> {
+    pub settings: ChunkSettings,
+    pub _phantom: PhantomData<(WorleyResT, BiomeT, Picker)>,
+}
+
+impl<WorleyResT, BiomeT, Picker> Default for ChunkPlugin<WorleyResT, BiomeT, Picker> {
+    fn default() -> Self {
+        Self {
+            settings: ChunkSettings::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<WorleyResT, BiomeT, Picker> Plugin for ChunkPlugin<WorleyResT, BiomeT, Picker>
+where
+    WorleyResT: Resource + GetWorley<BiomeT, Picker>,
+    BiomeT: BiomeVariants + Default + Send + Sync + 'static,
+    Picker: BiomePicker<BiomeT> + Default + Clone + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        if let Err(err) = self.settings.validate() {
+            panic!("invalid ChunkPlugin settings: {err}");
+        }
+        app.insert_resource(self.settings)
+            .init_resource::<ChunkMap>()
+            .add_message::<ChunkGenerated>()
+            .add_systems(
+                PostUpdate,
+                (
+                    snapshot_worley::<WorleyResT, BiomeT, Picker>,
+                    load_chunks_in_range,
+                    spawn_chunk_tasks::<BiomeT, Picker>,
+                    poll_chunk_tasks::<BiomeT>,
+                )
+                    .chain()
+                    .after(TransformSystems::Propagate),
+            );
+    }
+}
+
+/// can be changed at runtime, chunks are resampled when chunk_size or resolution change.
+/// while validate() fails no chunks are loaded, unloaded or sampled
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct ChunkSettings {
+    /// world size of a chunk
+    pub chunk_size: f64,
+    /// samples per chunk axis
+    pub resolution: usize,
+    /// chunks within this many chunks of a loader are generated
+    pub load_distance: i32,
+    /// chunks further than this are despawned, keep it above load_distance
+    /// so chunks on the border don't flicker in and out
+    pub unload_distance: i32,
+    /// chunks sampled at the same time, the closest ones go first
+    pub max_tasks: usize,
+}
+
+impl Default for ChunkSettings {
+    fn default() -> Self {
+        Self {
+            chunk_size: 32.0,
+            resolution: 32,
+            load_distance: 4,
+            unload_distance: 5,
+            max_tasks: 8,
+        }
+    }
+}
+
+impl ChunkSettings {
+    /// error for a chunk_size that isn't finite and above 0,
+    /// or distances that aren't 0 <= load_distance <= unload_distance
+    pub fn validate(&self) -> Result<(), WorleyError> {
+        if !(self.chunk_size.is_finite() && self.chunk_size > 0.0) {
+            return Err(WorleyError::InvalidChunkSize(self.chunk_size));
+        }
+        if self.load_distance < 0 || self.unload_distance < self.load_distance {
+            return Err(WorleyError::InvalidChunkDistance {
+                load: self.load_distance,
+                unload: self.unload_distance,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// chunks are generated around entities with a ChunkLoader, put it on your camera or player
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct ChunkLoader;
+
+/// chunk (x, z) covers Region::chunk(x, z, chunk_size)
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chunk(pub IVec2);
+
+/// the sampled biome weights of a chunk. kept while the chunk is resampled,
+/// so it is only missing before the first samples are ready
+#[derive(Component, Debug, Clone)]
+pub struct ChunkBiomes<BiomeT: Default>(pub SampleGrid<BiomeT>);
+
+/// the ChunkBiomes of (entity) were inserted or replaced
+#[derive(Message, Debug, Clone)]
+pub struct ChunkGenerated {
+    pub entity: Entity,
+    pub chunk: IVec2,
+}
+
+/// chunk entities by chunk position
+#[derive(Resource, Debug, Default)]
+pub struct ChunkMap {
+    chunks: HashMap<IVec2, Entity>,
+}
+
+impl ChunkMap {
+    pub fn get(&self, chunk: IVec2) -> Option<Entity> {
+        self.chunks.get(&chunk).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, Entity)> + '_ {
+        self.chunks.iter().map(|(chunk, entity)| (*chunk, *entity))
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+}
+
+/// chunk position containing world position (x, z), saturated at the ends of i32
+pub fn chunk_at(x: f64, z: f64, chunk_size: f64) -> IVec2 {
+    IVec2::new(
+        (x / chunk_size).floor() as i32,
+        (z / chunk_size).floor() as i32,
+    )
+}
+
+// the worley the tasks sample, a copy so the resource can change while they run
+#[derive(Resource)]
+struct WorleySnapshot<BiomeT, Picker>(Arc<Worley<BiomeT, Picker>>)
+where
+    BiomeT: BiomeVariants,
+    Picker: BiomePicker<BiomeT> + Default;
+
+// waiting for a free task slot
+#[derive(Component)]
+struct NeedsSampling;
+
+// dropping it cancels the task
+#[derive(Component)]
+struct ChunkTask<BiomeT: Default>(Task<SampleGrid<BiomeT>>);
+
+fn snapshot_worley<WorleyResT, BiomeT, Picker>(
+    mut commands: Commands,
+    worley_res: Res<WorleyResT>,
+    settings: Res<ChunkSettings>,
+    chunks: Query<Entity, With<Chunk>>,
+) where
+    WorleyResT: Resource + GetWorley<BiomeT, Picker>,
+    BiomeT: BiomeVariants + Default + Send + Sync + 'static,
+    Picker: BiomePicker<BiomeT> + Default + Clone + Send + Sync + 'static,
+{
+    if !worley_res.is_changed() && !settings.is_changed() {
+        return;
+    }
+    let worley = worley_res.get_worley().clone();
+    commands.insert_resource(WorleySnapshot(Arc::new(worley)));
+    // outdated tasks are cancelled, the chunks wait for a slot again
+    for entity in chunks.iter() {
+        commands
+            .entity(entity)
+            .remove::<ChunkTask<BiomeT>>()
+            .insert(NeedsSampling);
+    }
+}
+
+fn load_chunks_in_range(
+    mut commands: Commands,
+    settings: Res<ChunkSettings>,
+    mut chunk_map: ResMut<ChunkMap>,
+    loaders: Query<&GlobalTransform, With<ChunkLoader>>,
+) {
+    if let Err(err) = settings.validate() {
+        if settings.is_changed() {
+            error!("chunks are paused: {}", err);
+        }
+        return;
+    }
+    let centers: Vec<IVec2> = loaders
+        .iter()
+        .map(|transform| {
+            let position = transform.translation();
+            chunk_at(position.x as f64, position.z as f64, settings.chunk_size)
+        })
+        .collect();
+    // in i64, centers can be saturated
+    let distance = |chunk: IVec2| {
+        centers
+            .iter()
+            .map(|center| {
+                (chunk.as_i64vec2() - center.as_i64vec2())
+                    .abs()
+                    .max_element()
+            })
+            .min()
+            .unwrap_or(i64::MAX)
+    };
+
+    chunk_map.chunks.retain(|chunk, entity| {
+        let keep = distance(*chunk) <= settings.unload_distance as i64;
+        if !keep {
+            commands.entity(*entity).despawn();
+        }
+        keep
+    });
+
+    let range = settings.load_distance;
+    for center in centers.iter() {
+        for x in -range..=range {
+            for z in -range..=range {
+                let chunk = center.saturating_add(IVec2::new(x, z));
+                chunk_map
+                    .chunks
+                    .entry(chunk)
+                    .or_insert_with(|| commands.spawn((Chunk(chunk), NeedsSampling)).id());
+            }
+        }
+    }
+}
+
+fn spawn_chunk_tasks<BiomeT, Picker>(
+    mut commands: Commands,
+    settings: Res<ChunkSettings>,
+    snapshot: Option<Res<WorleySnapshot<BiomeT, Picker>>>,
+    waiting: Query<(Entity, &Chunk), With<NeedsSampling>>,
+    running: Query<(), With<ChunkTask<BiomeT>>>,
+    loaders: Query<&GlobalTransform, With<ChunkLoader>>,
+) where
+    BiomeT: BiomeVariants + Default + Send + Sync + 'static,
+    Picker: BiomePicker<BiomeT> + Default + Send + Sync + 'static,
+{
+    let Some(snapshot) = snapshot else {
+        return;
+    };
+    if settings.validate().is_err() {
+        return;
+    }
+    let free = settings.max_tasks.saturating_sub(running.iter().count());
+    if free == 0 || waiting.is_empty() {
+        return;
+    }
+    // closest to any loader first
+    let loaders: Vec<Vec2> = loaders
+        .iter()
+        .map(|transform| transform.translation().xz())
+        .collect();
+    let chunk_size = settings.chunk_size;
+    let distance = |chunk: IVec2| {
+        let center = (chunk.as_dvec2() + 0.5) * chunk_size;
+        loaders
+            .iter()
+            .map(|loader| center.as_vec2().distance_squared(*loader))
+            .fold(f32::MAX, f32::min)
+    };
+    let mut waiting: Vec<(Entity, IVec2)> = waiting
+        .iter()
+        .map(|(entity, chunk)| (entity, chunk.0))
+        .collect();
+    waiting.sort_by(|a, b| distance(a.1).total_cmp(&distance(b.1)));
+
+    let task_pool = AsyncComputeTaskPool::get();
+    let resolution = settings.resolution;
+    for (entity, chunk) in waiting.into_iter().take(free) {
+        let worley = snapshot.0.clone();
+        let region = Region::chunk(chunk.x, chunk.y, chunk_size);
+        let task =
+            task_pool.spawn(async move { worley.sample_region(region, resolution, resolution) });
+        commands
+            .entity(entity)
+            .remove::<NeedsSampling>()
+            .insert(ChunkTask(task));
+    }
+}
+
+fn poll_chunk_tasks<BiomeT>(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &Chunk, &mut ChunkTask<BiomeT>)>,
+    mut generated: MessageWriter<ChunkGenerated>,
+) where
+    BiomeT: BiomeVariants + Default + Send + Sync + 'static,
+{
+    for (entity, chunk, mut task) in tasks.iter_mut() {
+        let Some(grid) = check_ready(&mut task.0) else {
+            continue;
+        };
+        commands
+            .entity(entity)
+            .remove::<ChunkTask<BiomeT>>()
+            .insert(ChunkBiomes(grid));
+        generated.write(ChunkGenerated {
+            entity,
+            chunk: chunk.0,
+        });
+    }
+}
@@ -0,0 +1,264 @@
+use bevy::prelude::*;
+
+use std::any::type_name;
+use std::marker::PhantomData;
+
+use bevy::asset::{AssetLoadFailedEvent, AssetLoader, AssetPath, LoadContext, io::Reader};
+use bevy::reflect::utility::GenericTypePathCell;
+use serde::Deserialize;
+
+use crate::{
+    biome_picker::{BiomePicker, BiomeVariants},
+    config::ConfigError,
+    worley::Worley,
+};
+
+use super::GetWorley;
+
+/// a .worley.ron config loaded through the AssetServer, so it can be referenced
+/// from scenes and hot reloaded (enable bevy's "file_watcher" feature)
+#[derive(Asset)]
+pub struct WorleyConfig<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants + Send + Sync + 'static,
+    Picker: BiomePicker<BiomeT> + Default + Send + Sync + 'static,
+{
+    pub worley: Worley<BiomeT, Picker>,
+}
+
+impl<BiomeT, Picker> WorleyConfig<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants + Send + Sync + 'static + for<'de> Deserialize<'de>,
+    Picker:
+        BiomePicker<BiomeT> + Default + Clone + Send + Sync + 'static + for<'de> Deserialize<'de>,
+{
+    /// a copy of the loaded worley
+    pub fn to_worley(&self) -> Worley<BiomeT, Picker> {
+        self.worley.clone()
+    }
+}
+
+// derive(TypePath) would require TypePath on the biome enum and picker
+impl<BiomeT, Picker> TypePath for WorleyConfig<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants + Send + Sync + 'static,
+    Picker: BiomePicker<BiomeT> + Default + Send + Sync + 'static,
+{
+    fn type_path() -> &'static str {
+        static CELL: GenericTypePathCell = GenericTypePathCell::new();
+        CELL.get_or_insert::<Self, _>(|| {
+            format!(
+                "worley_biomes::bevy::config_asset::WorleyConfig<{}, {}>",
+                type_name::<BiomeT>(),
+                type_name::<Picker>()
+            )
+        })
+    }
+
+    fn short_type_path() -> &'static str {
+        static CELL: GenericTypePathCell = GenericTypePathCell::new();
+        CELL.get_or_insert::<Self, _>(|| {
+            format!(
+                "WorleyConfig<{}, {}>",
+                short_name(type_name::<BiomeT>()),
+                short_name(type_name::<Picker>())
+            )
+        })
+    }
+
+    fn type_ident() -> Option<&'static str> {
+        Some("WorleyConfig")
+    }
+
+    fn crate_name() -> Option<&'static str> {
+        Some("worley_biomes")
+    }
+
+    fn module_path() -> Option<&'static str> {
+        Some("worley_biomes::bevy::config_asset")
+    }
+}
+
+// last path segment, good enough for names in logs and inspectors
+fn short_name(type_name: &str) -> &str {
+    let without_generics = type_name.split('<').next().unwrap_or(type_name);
+    let start = without_generics.rfind("::").map_or(0, |i| i + 2);
+    &type_name[start..]
+}
+
+/// loads .worley.ron files of any config version
+pub struct WorleyConfigLoader<BiomeT, Picker> {
+    _phantom: PhantomData<(BiomeT, Picker)>,
+}
+
+impl<BiomeT, Picker> Default for WorleyConfigLoader<BiomeT, Picker> {
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<BiomeT, Picker> AssetLoader for WorleyConfigLoader<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants + Send + Sync + 'static + for<'de> Deserialize<'de>,
+    Picker: BiomePicker<BiomeT> + Default + Send + Sync + 'static + for<'de> Deserialize<'de>,
+{
+    type Asset = WorleyConfig<BiomeT, Picker>;
+    type Settings = ();
+    type Error = ConfigError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let io_error = |err: String| ConfigError::Io {
+            path: load_context.path().display().to_string(),
+            err,
+        };
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|err| io_error(err.to_string()))?;
+        let ron = std::str::from_utf8(&bytes).map_err(|err| io_error(err.to_string()))?;
+        Ok(WorleyConfig {
+            worley: Worley::from_ron(ron)?,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["worley.ron"]
+    }
+}
+
+/// the config that drives the GetWorley resource, it's replaced when the config
+/// loads and on every hot reload. set it to switch configs
+#[derive(Resource)]
+pub struct ActiveWorleyConfig<BiomeT, Picker>(pub Handle<WorleyConfig<BiomeT, Picker>>)
+where
+    BiomeT: BiomeVariants + Send + Sync + 'static,
+    Picker: BiomePicker<BiomeT> + Default + Send + Sync + 'static;
+
+/// a config failed to load (file missing, invalid RON or settings)
+#[derive(Message, Debug, Clone)]
+pub struct WorleyConfigError {
+    pub path: Option<AssetPath<'static>>,
+    pub error: String,
+}
+
+/// loads .worley.ron assets and keeps the WorleyResT resource in sync with ActiveWorleyConfig.
+/// the resource has to exist, it's updated in place (triggering a preview rebuild in DebugPlugin)
+pub struct WorleyConfigPlugin<WorleyResT, BiomeT, Picker> {
+    /// loaded on startup and made the ActiveWorleyConfig, relative to the assets folder
+    pub path: Option<String>,
+    pub _phantom: PhantomData<(WorleyResT, BiomeT, Picker)>,
+}
+
+impl<WorleyResT, BiomeT, Picker> WorleyConfigPlugin<WorleyResT, BiomeT, Picker> {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: Some(path.into()),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<WorleyResT, BiomeT, Picker> Default for WorleyConfigPlugin<WorleyResT, BiomeT, Picker> {
+    fn default() -> Self {
+        Self {
+            path: None,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<WorleyResT, BiomeT, Picker> Plugin for WorleyConfigPlugin<WorleyResT, BiomeT, Picker>
+where
+    WorleyResT: Resource + GetWorley<BiomeT, Picker>,
+    BiomeT: BiomeVariants + Send + Sync + 'static + for<'de> Deserialize<'de>,
+    Picker:
+        BiomePicker<BiomeT> + Default + Clone + Send + Sync + 'static + for<'de> Deserialize<'de>,
+{
+    fn build(&self, app: &mut App) {
+        app.init_asset::<WorleyConfig<BiomeT, Picker>>()
+            .register_asset_loader(WorleyConfigLoader::<BiomeT, Picker>::default())
+            .add_message::<WorleyConfigError>()
+            .add_systems(
+                Update,
+                (
+                    report_load_errors::<BiomeT, Picker>,
+                    apply_worley_config::<WorleyResT, BiomeT, Picker>,
+                ),
+            );
+        if let Some(path) = self.path.clone() {
+            app.add_systems(
+                Startup,
+                move |mut commands: Commands, asset_server: Res<AssetServer>| {
+                    let handle = asset_server.load(&path);
+                    commands.insert_resource(ActiveWorleyConfig::<BiomeT, Picker>(handle));
+                },
+            );
+        }
+    }
+}
+
+fn report_load_errors<BiomeT, Picker>(
+    mut failed: MessageReader<AssetLoadFailedEvent<WorleyConfig<BiomeT, Picker>>>,
+    mut errors: MessageWriter<WorleyConfigError>,
+) where
+    BiomeT: BiomeVariants + Send + Sync + 'static,
+    Picker: BiomePicker<BiomeT> + Default + Send + Sync + 'static,
+{
+    for failed in failed.read() {
+        error!(
+            "failed to load worley config {}: {}",
+            failed.path, failed.error
+        );
+        errors.write(WorleyConfigError {
+            path: Some(failed.path.clone()),
+            error: failed.error.to_string(),
+        });
+    }
+}
+
+// copy the active config into WorleyResT when it (re)loads or is switched
+fn apply_worley_config<WorleyResT, BiomeT, Picker>(
+    mut asset_events: MessageReader<AssetEvent<WorleyConfig<BiomeT, Picker>>>,
+    active: Option<Res<ActiveWorleyConfig<BiomeT, Picker>>>,
+    configs: Res<Assets<WorleyConfig<BiomeT, Picker>>>,
+    asset_server: Res<AssetServer>,
+    worley_res: Option<ResMut<WorleyResT>>,
+) where
+    WorleyResT: Resource + GetWorley<BiomeT, Picker>,
+    BiomeT: BiomeVariants + Send + Sync + 'static + for<'de> Deserialize<'de>,
+    Picker:
+        BiomePicker<BiomeT> + Default + Clone + Send + Sync + 'static + for<'de> Deserialize<'de>,
+{
+    let Some(active) = active else {
+        asset_events.clear();
+        return;
+    };
+    let id = active.0.id();
+    let reloaded = asset_events.read().any(|event| match event {
+        AssetEvent::LoadedWithDependencies { id: event_id }
+        | AssetEvent::Modified { id: event_id } => *event_id == id,
+        _ => false,
+    });
+    // switched to a config that was already loaded, no load event for it
+    let switched = active.is_changed() && configs.contains(id);
+    if !reloaded && !switched {
+        return;
+    }
+    let Some(config) = configs.get(id) else {
+        return;
+    };
+    let Some(mut worley_res) = worley_res else {
+        warn!("worley config loaded, but there is no resource to apply it to");
+        return;
+    };
+    *worley_res.get_worley_mut() = config.to_worley();
+    info!("applied worley config {:?}", asset_server.get_path(id));
+}
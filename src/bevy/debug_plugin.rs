@@ -1,26 +1,37 @@
 use bevy::prelude::*;
 
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use crate::{
+    algorithm::AlgorithmVersion,
     biome_picker::{BiomePicker, BiomeVariants},
+    blend::BiomeBlend,
     distance_fn::DistanceFn,
-    worley::Worley,
+    inspect::CandidateFate,
+    noise::{Noise, NoiseKind},
+    registry::BiomeId,
+    seed::WorldSeed,
+    worley::{MAX_K, Worley},
 };
 use bevy::{
     asset::RenderAssetUsages,
     image::ImageSampler,
+    input::mouse::{MouseScrollUnit, MouseWheel},
     render::render_resource::{Extent3d, TextureDimension},
+    tasks::{AsyncComputeTaskPool, Task, futures::check_ready},
+    ui::RelativeCursorPosition,
 };
 use bevy_inspector_egui::{
     bevy_egui::{self, EguiContext, EguiPrimaryContextPass},
     egui,
+    inspector_egui_impls::InspectorPrimitive,
+    reflect_inspector::InspectorUi,
 };
 use bracket_fast_noise::prelude::*;
 
 #[cfg(feature = "serde")]
-use ron::ser::PrettyConfig;
-
+use super::config_asset::{ActiveWorleyConfig, WorleyConfig};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -44,6 +55,11 @@ pub struct DebugPluginSettings {
     // preview + ui visibility controll
     pub show_preview_image: bool,
     pub show_inspector_ui: bool,
+
+    ///! pixels per side of the preview image, sampled in the background
+    pub preview_resolution: u32,
+    ///! world units across the preview image, at WorleyImage::preview_zoom 1.0
+    pub preview_extent: f64,
 }
 
 impl Default for DebugPluginSettings {
@@ -52,6 +68,8 @@ impl Default for DebugPluginSettings {
             spawn_preview_image: true,
             show_preview_image: true,
             show_inspector_ui: true,
+            preview_resolution: IMG_SIZE as u32,
+            preview_extent: IMG_SIZE as f64,
         }
     }
 }
@@ -73,6 +91,7 @@ where
         + for<'de> Deserialize<'de>,
     Picker: BiomePicker<BiomeT>
         + Default
+        + Clone
         + Sync
         + Send
         + 'static
@@ -80,16 +99,27 @@ where
         + for<'de> Deserialize<'de>,
 {
     fn build(&self, app: &mut App) {
-        app.insert_resource(self.settings.clone());
+        app.insert_resource(self.settings.clone())
+            .init_resource::<PreviewRebuild>();
         app.add_systems(
             EguiPrimaryContextPass,
-            inspector_ui::<WorleyResT, BiomeT, Picker>.run_if(if_show_inspector),
+            (
+                inspector_ui::<WorleyResT, BiomeT, Picker>.run_if(if_show_inspector),
+                preview_tooltip::<WorleyResT, BiomeT, Picker>,
+            ),
+        );
+        app.add_systems(
+            Update,
+            (preview_buttons, pan_zoom_preview, update_preview_readout),
         );
-        app.add_systems(Update, texture_tap);
         app.add_systems(Update, update_preview_visibility);
         app.add_systems(
             PostUpdate,
-            rebuild_preview_image::<WorleyResT, BiomeT, Picker>,
+            (
+                rebuild_preview_image::<WorleyResT, BiomeT, Picker>,
+                apply_preview_passes,
+            )
+                .chain(),
         );
     }
 }
@@ -100,19 +130,30 @@ impl<WorleyResT, BiomeT, Picker> Plugin for DebugPlugin<WorleyResT, BiomeT, Pick
 where
     WorleyResT: Resource + GetWorley<BiomeT, Picker>,
     BiomeT: BiomeVariants + DebugColor<BiomeT> + Sync + Send + std::default::Default + 'static,
-    Picker: BiomePicker<BiomeT> + Default + Sync + Send + 'static,
+    Picker: BiomePicker<BiomeT> + Default + Clone + Sync + Send + 'static,
 {
     fn build(&self, app: &mut App) {
-        app.insert_resource(self.settings.clone());
+        app.insert_resource(self.settings.clone())
+            .init_resource::<PreviewRebuild>();
         app.add_systems(
             EguiPrimaryContextPass,
-            inspector_ui::<WorleyResT, BiomeT, Picker>.run_if(if_show_inspector),
+            (
+                inspector_ui::<WorleyResT, BiomeT, Picker>.run_if(if_show_inspector),
+                preview_tooltip::<WorleyResT, BiomeT, Picker>,
+            ),
+        );
+        app.add_systems(
+            Update,
+            (preview_buttons, pan_zoom_preview, update_preview_readout),
         );
-        app.add_systems(Update, texture_tap);
         app.add_systems(Update, update_preview_visibility);
         app.add_systems(
             PostUpdate,
-            rebuild_preview_image::<WorleyResT, BiomeT, Picker>,
+            (
+                rebuild_preview_image::<WorleyResT, BiomeT, Picker>,
+                apply_preview_passes,
+            )
+                .chain(),
         );
     }
 }
@@ -122,20 +163,31 @@ pub fn if_show_inspector(settings: Res<DebugPluginSettings>) -> bool {
 }
 
 ///! color of biome to display in debug worley texture
-pub trait DebugColor<BiomeT> {
+pub trait DebugColor<BiomeT>: std::fmt::Debug {
     fn get_color(&self) -> Srgba;
+
+    ///! name shown in the preview tooltip, next to the color, the Debug output by default
+    fn debug_name(&self) -> String {
+        format!("{self:?}")
+    }
 }
 
-///! required for the debug_plugin to find what worley to visualize
-pub trait GetWorley<BiomeT, Picker>
-where
-    BiomeT: BiomeVariants,
-    Picker: BiomePicker<BiomeT> + Default,
-{
-    fn get_worley<'a>(&'a self) -> &'a Worley<BiomeT, Picker>;
-    fn get_worley_mut<'a>(&'a mut self) -> &'a mut Worley<BiomeT, Picker>;
+/// registry biomes get an automatic color, spread out around the hue circle
+impl DebugColor<BiomeId> for BiomeId {
+    fn get_color(&self) -> Srgba {
+        // golden angle, keeps neighboring ids apart
+        let hue = (self.0 as f32 * 137.508) % 360.0;
+        Srgba::from(Hsla::hsl(hue, 0.7, 0.5))
+    }
+
+    fn debug_name(&self) -> String {
+        format!("#{}", self.0)
+    }
 }
 
+// moved next to the runtime plugin, still reachable from here
+pub use super::GetWorley;
+
 ///! size of the visible worley texture on screen
 #[derive(Component, Default)]
 enum DisplayTextureSize {
@@ -153,123 +205,381 @@ impl DisplayTextureSize {
             DisplayTextureSize::Big => *self = DisplayTextureSize::Auto,
         }
     }
-    pub fn node_size(&self) -> bevy::ui::Val {
+    ///! Auto shows one pixel per image pixel at full (resolution),
+    ///! so the smaller coarse pass doesn't shrink the node
+    pub fn node_size(&self, resolution: u32) -> bevy::ui::Val {
         match self {
-            DisplayTextureSize::Auto => bevy::ui::Val::Auto,
+            DisplayTextureSize::Auto => bevy::ui::Val::Px(resolution as f32),
             DisplayTextureSize::Medium => bevy::ui::Val::Px(300.0),
             DisplayTextureSize::Big => bevy::ui::Val::Px(600.0),
         }
     }
 }
 
-///! the size of the preview image
+///! default size of the preview image, see DebugPluginSettings::preview_resolution
 pub const IMG_SIZE: i32 = 32 * 4;
 
-///! toggle the preview image size
-fn texture_tap(
-    mut interaction_query: Query<
-        (&Interaction, &mut DisplayTextureSize, &mut Node),
-        (Changed<Interaction>, With<Button>),
-    >,
+///! the first pass of a rebuild samples every COARSE_STEP-th pixel,
+///! into an image COARSE_STEP times smaller
+pub const COARSE_STEP: u32 = 4;
+
+///! buttons in the corner of the preview image
+#[derive(Component, Clone, Copy)]
+enum PreviewButton {
+    // toggle the preview image size
+    Size,
+    // back to the initial offset and zoom
+    Reset,
+}
+
+///! world position under the cursor, shown on the preview image
+#[derive(Component)]
+struct PreviewReadout;
+
+fn preview_buttons(
+    buttons: Query<(&Interaction, &PreviewButton), Changed<Interaction>>,
+    mut previews: Query<(&mut DisplayTextureSize, &mut Node), With<WorleyUiPreviewTag>>,
+    mut worley_image: Option<ResMut<WorleyImage>>,
+    settings: Res<DebugPluginSettings>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            PreviewButton::Size => {
+                for (mut display_size, mut node) in previews.iter_mut() {
+                    display_size.toggle();
+                    node.width = display_size.node_size(settings.preview_resolution.max(1));
+                }
+            }
+            PreviewButton::Reset => {
+                if let Some(worley_image) = &mut worley_image {
+                    worley_image.reset();
+                }
+            }
+        }
+    }
+}
+
+///! drag to pan, scroll to zoom the preview image
+fn pan_zoom_preview(
+    previews: Query<&RelativeCursorPosition, With<WorleyUiPreviewTag>>,
+    buttons: Query<&Interaction, With<PreviewButton>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut wheel: MessageReader<MouseWheel>,
+    // uv under the cursor last frame, while dragging
+    mut dragging: Local<Option<Vec2>>,
+    worley_image: Option<ResMut<WorleyImage>>,
+    mut egui_context: Option<Single<&mut EguiContext, With<bevy_egui::PrimaryEguiContext>>>,
 ) {
-    for (interaction, mut display_size, mut node) in interaction_query.iter_mut() {
-        if let Interaction::Pressed = interaction {
-            // toggle
-            display_size.toggle();
-            node.width = display_size.node_size();
+    let scroll: f32 = wheel
+        .read()
+        .map(|wheel| match wheel.unit {
+            MouseScrollUnit::Line => wheel.y,
+            MouseScrollUnit::Pixel => wheel.y / 100.0,
+        })
+        .sum();
+    let Some(mut worley_image) = worley_image else {
+        return;
+    };
+    let Some(cursor) = previews
+        .iter()
+        .find_map(|cursor| Some((cursor.normalized? + 0.5, cursor.cursor_over())))
+    else {
+        *dragging = None;
+        return;
+    };
+    let (uv, mut hovered) = cursor;
+    // the inspector window is on top of the preview, it gets the clicks and scrolls
+    if let Some(egui_context) = egui_context.as_mut()
+        && egui_context.get_mut().is_pointer_over_area()
+    {
+        hovered = false;
+    }
+    let on_button = buttons.iter().any(|i| *i != Interaction::None);
+
+    if mouse.just_pressed(MouseButton::Left) && hovered && !on_button {
+        *dragging = Some(uv);
+    }
+    if !mouse.pressed(MouseButton::Left) {
+        *dragging = None;
+    }
+    if let Some(last) = *dragging
+        && last != uv
+    {
+        worley_image.pan(uv - last);
+        *dragging = Some(uv);
+    }
+    if scroll != 0.0 && hovered {
+        worley_image.zoom_at(uv, 1.1f64.powf(-scroll as f64));
+    }
+}
+
+fn update_preview_readout(
+    previews: Query<&RelativeCursorPosition, With<WorleyUiPreviewTag>>,
+    mut readouts: Query<&mut Text, With<PreviewReadout>>,
+    worley_image: Option<Res<WorleyImage>>,
+    settings: Res<DebugPluginSettings>,
+) {
+    let Some(worley_image) = worley_image else {
+        return;
+    };
+    let hovered = previews
+        .iter()
+        .filter(|cursor| cursor.cursor_over())
+        .find_map(|cursor| cursor.normalized);
+    let text = match hovered {
+        Some(normalized) => {
+            let (x, z) = worley_image.world_position(normalized + 0.5);
+            format!("x {x:.1}  z {z:.1}")
+        }
+        None => {
+            let (x, z) = worley_image.world_position(Vec2::splat(0.5));
+            format!("center x {x:.1}  z {z:.1}")
+        }
+    };
+    let per_px = worley_image.world_size() / settings.preview_resolution.max(1) as f64;
+    let text = format!("{text}  ({per_px:.2} per px)");
+    for mut readout in readouts.iter_mut() {
+        if readout.0 != text {
+            readout.0 = text.clone();
         }
     }
 }
 
-///! fetch worley data to UPDATE the preview image
+///! start sampling the preview image in the background, a coarse pass first and the
+///! full resolution after. a rebuild while the passes run cancels them
 fn rebuild_preview_image<WorleyResT, BiomeT, Picker>(
     map_settings: Res<WorleyResT>,
     debug_plugin_settings: Res<DebugPluginSettings>,
     mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
-    mut worley_image: Option<ResMut<WorleyImage>>,
+    worley_image: Option<ResMut<WorleyImage>>,
+    mut rebuild: ResMut<PreviewRebuild>,
 ) where
     WorleyResT: Resource + GetWorley<BiomeT, Picker>,
-    BiomeT: BiomeVariants + 'static + DebugColor<BiomeT> + std::default::Default,
-    Picker: BiomePicker<BiomeT> + Default + 'static,
+    BiomeT: BiomeVariants + DebugColor<BiomeT> + std::default::Default + Send + Sync + 'static,
+    Picker: BiomePicker<BiomeT> + Default + Clone + Send + Sync + 'static,
 {
-    if !map_settings.is_changed() {
+    let Some(mut worley_image) = worley_image else {
+        spawn_preview(&mut commands, &mut images, &debug_plugin_settings);
+        return;
+    };
+    // the worley or the preview settings changed, or the preview was moved / zoomed
+    if !map_settings.is_changed()
+        && !debug_plugin_settings.is_changed()
+        && !worley_image.is_changed()
+    {
         return;
     }
+    // only a copy of the settings, changing it isn't a reason to rebuild again
+    worley_image.bypass_change_detection().extent = debug_plugin_settings.preview_extent;
+
+    let view = PreviewView::new(&worley_image, debug_plugin_settings.preview_resolution);
+    let worley = Arc::new(map_settings.get_worley().clone());
+    let task_pool = AsyncComputeTaskPool::get();
+    // dropping the running passes cancels them
+    rebuild.passes = [COARSE_STEP, 1]
+        .into_iter()
+        .map(|step| {
+            let worley = worley.clone();
+            task_pool.spawn(async move { sample_preview(&worley, view, step) })
+        })
+        .collect();
+}
 
-    let mut img_data = Vec::new();
-    let worley = WorleyResT::get_worley(&map_settings);
+///! show the most refined pass that finished
+fn apply_preview_passes(
+    mut rebuild: ResMut<PreviewRebuild>,
+    worley_image: Option<Res<WorleyImage>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Some(worley_image) = worley_image else {
+        return;
+    };
+    let mut finished = None;
+    for (i, task) in rebuild.passes.iter_mut().enumerate() {
+        if let Some(img_data) = check_ready(task) {
+            finished = Some((i, img_data));
+        }
+    }
+    let Some((i, pass)) = finished else {
+        return;
+    };
+    // coarser passes are outdated now, finished or not
+    rebuild.passes.drain(..=i);
+    let Some(image) = images.get_mut(&worley_image.handle) else {
+        return;
+    };
+    if image.width() == pass.size {
+        image.data = Some(pass.data);
+    } else {
+        // the ui scales it up, nearest sampling keeps the coarse pixels sharp
+        *image = preview_image(pass.size, pass.data);
+    }
+}
 
-    let worley_offset = worley_image
-        .as_mut()
-        .map_or((0.0, 0.0), |w| w.preview_offset);
-    for gx in 0..IMG_SIZE {
-        for gz in 0..IMG_SIZE {
-            let weights = worley.get(gx as f64 + worley_offset.0, gz as f64 + worley_offset.1);
+///! running passes of the preview image, coarse first
+#[derive(Resource, Default)]
+struct PreviewRebuild {
+    passes: Vec<Task<PreviewPass>>,
+}
 
-            // blend colors
-            let mut r = 0.0;
-            let mut g = 0.0;
-            let mut b = 0.0;
-            let mut wsum = 0.0;
-            for (w, biome) in &weights {
-                let c = DebugColor::get_color(biome);
-                r += c.red as f64 * w;
-                g += c.green as f64 * w;
-                b += c.blue as f64 * w;
-                wsum += w;
-            }
+///! the world area a preview rebuild samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreviewView {
+    pub offset: (f64, f64),
+    pub units_per_px: f64,
+    ///! pixels per side at full resolution
+    pub resolution: u32,
+}
 
-            let color = Srgba::new(r as f32, g as f32, b as f32, 1.0);
-            img_data.push((color.red * 255.0) as u8);
-            img_data.push((color.green * 255.0) as u8);
-            img_data.push((color.blue * 255.0) as u8);
-            img_data.push(255 as u8);
+impl PreviewView {
+    ///! what (worley_image) shows at (resolution) pixels per side
+    pub fn new(worley_image: &WorleyImage, resolution: u32) -> Self {
+        let resolution = resolution.max(1);
+        Self {
+            offset: worley_image.preview_offset,
+            units_per_px: worley_image.world_size() / resolution as f64,
+            resolution,
         }
     }
+}
 
-    match worley_image {
-        Some(worley_image) => {
-            let image = images.get_mut(&worley_image.handle).expect("image");
-            image.data = Some(img_data);
-        }
-        None => {
-            // make image
-            let mut img = Image::new(
-                Extent3d {
-                    width: IMG_SIZE as u32,
-                    height: IMG_SIZE as u32,
-                    depth_or_array_layers: 1,
-                },
-                TextureDimension::D2,
-                img_data,
-                bevy::render::render_resource::TextureFormat::Rgba8UnormSrgb,
-                RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+///! RGBA pixels of one preview pass, size * size
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviewPass {
+    pub size: u32,
+    pub data: Vec<u8>,
+}
+
+///! samples every (step)-th pixel of (view), one pixel each,
+///! the pass is view.resolution / step pixels per side (rounded up)
+pub fn sample_preview<BiomeT, Picker>(
+    worley: &Worley<BiomeT, Picker>,
+    view: PreviewView,
+    step: u32,
+) -> PreviewPass
+where
+    BiomeT: BiomeVariants + DebugColor<BiomeT> + std::default::Default + 'static,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    let step = step.max(1);
+    let size = view.resolution.max(1).div_ceil(step);
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    // image rows go along x, columns along z
+    for px in 0..size {
+        for pz in 0..size {
+            let (gx, gz) = (px * step, pz * step);
+            let weights = worley.get(
+                gx as f64 * view.units_per_px + view.offset.0,
+                gz as f64 * view.units_per_px + view.offset.1,
             );
-            img.sampler = ImageSampler::nearest();
-            let image_handle = images.add(img);
 
-            // spawn visual representation
-            if debug_plugin_settings.spawn_preview_image {
-                commands.spawn((
-                    Name::new("worley_ui_preview"),
+            // blend colors
+            let color = weights
+                .blend(|biome| biome.get_color())
+                .unwrap_or(Srgba::BLACK);
+            data.extend_from_slice(&[
+                (color.red * 255.0) as u8,
+                (color.green * 255.0) as u8,
+                (color.blue * 255.0) as u8,
+                255,
+            ]);
+        }
+    }
+    PreviewPass { size, data }
+}
+
+fn preview_image(resolution: u32, img_data: Vec<u8>) -> Image {
+    let mut img = Image::new(
+        Extent3d {
+            width: resolution,
+            height: resolution,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        img_data,
+        bevy::render::render_resource::TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    );
+    img.sampler = ImageSampler::nearest();
+    img
+}
+
+///! empty preview image + its ui, rebuild_preview_image fills it in
+fn spawn_preview(
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    debug_plugin_settings: &DebugPluginSettings,
+) {
+    let resolution = debug_plugin_settings.preview_resolution.max(1);
+    let image_handle = images.add(preview_image(
+        resolution,
+        vec![0; (resolution * resolution * 4) as usize],
+    ));
+
+    // spawn visual representation
+    if debug_plugin_settings.spawn_preview_image {
+        let button = |label: &str, button: PreviewButton| {
+            (
+                Button,
+                button,
+                Node {
+                    padding: UiRect::axes(Val::Px(4.0), Val::Px(1.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                children![(Text::new(label), TextFont::from_font_size(12.0))],
+            )
+        };
+        commands.spawn((
+            Name::new("worley_ui_preview"),
+            Node {
+                align_self: AlignSelf::Start,
+                width: DisplayTextureSize::default().node_size(resolution),
+                ..default()
+            },
+            ImageNode::new(image_handle.clone()),
+            DisplayTextureSize::default(),
+            WorleyUiPreviewTag,
+            RelativeCursorPosition::default(),
+            children![
+                (
                     Node {
-                        align_self: AlignSelf::Start,
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(2.0),
+                        right: Val::Px(2.0),
+                        column_gap: Val::Px(2.0),
                         ..default()
                     },
-                    ImageNode::new(image_handle.clone()),
-                    DisplayTextureSize::default(),
-                    WorleyUiPreviewTag,
-                    Button,
-                ));
-            }
-
-            commands.insert_resource(WorleyImage {
-                handle: image_handle,
-                preview_offset: (0.0, 0.0),
-            });
-        }
+                    children![
+                        button("size", PreviewButton::Size),
+                        button("reset", PreviewButton::Reset),
+                    ],
+                ),
+                (
+                    PreviewReadout,
+                    Text::default(),
+                    TextFont::from_font_size(12.0),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(2.0),
+                        bottom: Val::Px(2.0),
+                        padding: UiRect::horizontal(Val::Px(4.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                ),
+            ],
+        ));
     }
+
+    commands.insert_resource(WorleyImage::new(
+        image_handle,
+        debug_plugin_settings.preview_extent,
+    ));
 }
 
 #[derive(Component)]
@@ -277,12 +587,13 @@ pub struct WorleyUiPreviewTag;
 
 fn update_preview_visibility(
     settings: Res<DebugPluginSettings>,
-    mut query: Query<&mut Node, With<WorleyUiPreviewTag>>,
+    mut query: Query<(&mut Node, &DisplayTextureSize), With<WorleyUiPreviewTag>>,
 ) {
     if !settings.is_changed() {
         return;
     }
-    for mut node in query.iter_mut() {
+    for (mut node, display_size) in query.iter_mut() {
+        node.width = display_size.node_size(settings.preview_resolution.max(1));
         match settings.show_preview_image {
             true => {
                 node.display = Display::Flex;
@@ -295,11 +606,69 @@ fn update_preview_visibility(
 }
 
 ///! reference the preview image of the worley world
+///! drag the preview to pan, scroll to zoom
 #[derive(Resource)]
 pub struct WorleyImage {
     handle: Handle<Image>,
     ///! preview image sampling is offset by this
     pub preview_offset: (f64, f64),
+    ///! scales DebugPluginSettings::preview_extent, independent of Worley::zoom
+    pub preview_zoom: f64,
+    // copy of DebugPluginSettings::preview_extent
+    extent: f64,
+}
+
+impl WorleyImage {
+    ///! (handle) shows (extent) world units across, unpanned and unzoomed
+    pub fn new(handle: Handle<Image>, extent: f64) -> Self {
+        Self {
+            handle,
+            preview_offset: (0.0, 0.0),
+            preview_zoom: 1.0,
+            extent,
+        }
+    }
+
+    ///! the preview image, the most refined pass that finished so far
+    pub fn handle(&self) -> &Handle<Image> {
+        &self.handle
+    }
+
+    ///! world units across the preview image
+    pub fn world_size(&self) -> f64 {
+        self.extent * self.preview_zoom
+    }
+
+    ///! world (x, z) shown at (uv) of the preview image, uv goes 0..1 across the image.
+    ///! image rows go along x, columns along z
+    pub fn world_position(&self, uv: Vec2) -> (f64, f64) {
+        (
+            self.preview_offset.0 + uv.y as f64 * self.world_size(),
+            self.preview_offset.1 + uv.x as f64 * self.world_size(),
+        )
+    }
+
+    ///! move the sampled area so the world follows a cursor moved by (uv)
+    pub fn pan(&mut self, uv: Vec2) {
+        let world_size = self.world_size();
+        self.preview_offset.0 -= uv.y as f64 * world_size;
+        self.preview_offset.1 -= uv.x as f64 * world_size;
+    }
+
+    ///! scale preview_zoom by (factor), keeping the world position at (uv) in place
+    pub fn zoom_at(&mut self, uv: Vec2, factor: f64) {
+        let (x, z) = self.world_position(uv);
+        self.preview_zoom = (self.preview_zoom * factor).clamp(0.01, 100.0);
+        self.preview_offset = (
+            x - uv.y as f64 * self.world_size(),
+            z - uv.x as f64 * self.world_size(),
+        );
+    }
+
+    pub fn reset(&mut self) {
+        self.preview_offset = (0.0, 0.0);
+        self.preview_zoom = 1.0;
+    }
 }
 
 #[derive(Resource)]
@@ -341,8 +710,14 @@ where
 fn inspector_ui<WorleyResT, BiomeT, Picker>(mut world: &mut World)
 where
     WorleyResT: Resource + GetWorley<BiomeT, Picker>,
-    BiomeT: BiomeVariants + 'static + Serialize + for<'de> Deserialize<'de>,
-    Picker: BiomePicker<BiomeT> + Default + 'static + Serialize + for<'de> Deserialize<'de>,
+    BiomeT: BiomeVariants + Send + Sync + 'static + Serialize + for<'de> Deserialize<'de>,
+    Picker: BiomePicker<BiomeT>
+        + Default
+        + Send
+        + Sync
+        + 'static
+        + Serialize
+        + for<'de> Deserialize<'de>,
 {
     let mut egui_context = world
         .query_filtered::<&mut EguiContext, With<bevy_egui::PrimaryEguiContext>>()
@@ -361,9 +736,7 @@ where
             if ui.add(egui::Button::new("save worley to file")).clicked() {
                 let map_settings = world.get_resource::<WorleyResT>().expect("WorleyResT");
 
-                let deserialized =
-                    ron::ser::to_string_pretty(map_settings.get_worley(), PrettyConfig::default())
-                        .expect("deserialize");
+                let deserialized = map_settings.get_worley().to_ron().expect("serialize");
 
                 let path = format!("assets/{}.worley.ron", &file_name);
                 let result = std::fs::write(&path, deserialized);
@@ -371,26 +744,34 @@ where
             }
 
             if ui.add(egui::Button::new("load worley file")).clicked() {
-                let path = format!("assets/{}.worley.ron", &file_name);
-                let file = std::fs::read_to_string(&path);
-                match file {
-                    Ok(f) => {
-                        let result = ron::from_str::<Worley<BiomeT, Picker>>(&f);
-                        match result {
-                            Ok(new_worley) => {
-                                // REPLACE
-                                let mut map_settings = world.resource_mut::<WorleyResT>();
-                                let worley = map_settings.get_worley_mut();
-                                *worley = new_worley;
-                                info!("replaced current worley");
-                            }
-                            Err(err) => {
-                                error!("failed to deserialize worley: {:?}", err);
+                if world.contains_resource::<Assets<WorleyConfig<BiomeT, Picker>>>() {
+                    // WorleyConfigPlugin is added: load through the asset server, hot reloads
+                    let handle = world
+                        .resource::<AssetServer>()
+                        .load(format!("{}.worley.ron", &file_name));
+                    world.insert_resource(ActiveWorleyConfig::<BiomeT, Picker>(handle));
+                } else {
+                    let path = format!("assets/{}.worley.ron", &file_name);
+                    let file = std::fs::read_to_string(&path);
+                    match file {
+                        Ok(f) => {
+                            let result = Worley::<BiomeT, Picker>::from_ron(&f);
+                            match result {
+                                Ok(new_worley) => {
+                                    // REPLACE
+                                    let mut map_settings = world.resource_mut::<WorleyResT>();
+                                    let worley = map_settings.get_worley_mut();
+                                    *worley = new_worley;
+                                    info!("replaced current worley");
+                                }
+                                Err(err) => {
+                                    error!("failed to load worley {:?}: {}", path, err);
+                                }
                             }
                         }
-                    }
-                    Err(err) => {
-                        error!("err loading worley file: {:?}, {:?}", path, err);
+                        Err(err) => {
+                            error!("err loading worley file: {:?}, {:?}", path, err);
+                        }
                     }
                 }
             }
@@ -400,6 +781,102 @@ where
     });
 }
 
+///! hovering the preview shows how Worley::get() picks the biomes under the cursor,
+///! including the candidate cells dropped by k and kill_percent_threshold
+fn preview_tooltip<WorleyResT, BiomeT, Picker>(
+    mut egui_context: Single<&mut EguiContext, With<bevy_egui::PrimaryEguiContext>>,
+    previews: Query<&RelativeCursorPosition, With<WorleyUiPreviewTag>>,
+    worley_image: Option<Res<WorleyImage>>,
+    worley_res: Res<WorleyResT>,
+) where
+    WorleyResT: Resource + GetWorley<BiomeT, Picker>,
+    BiomeT: BiomeVariants + DebugColor<BiomeT> + std::default::Default + 'static,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    let Some(worley_image) = worley_image else {
+        return;
+    };
+    let Some(normalized) = previews
+        .iter()
+        .filter(|cursor| cursor.cursor_over())
+        .find_map(|cursor| cursor.normalized)
+    else {
+        return;
+    };
+    let ctx = egui_context.get_mut();
+    // the inspector window is on top of the preview
+    if ctx.is_pointer_over_area() {
+        return;
+    }
+    let Some(pointer) = ctx.pointer_hover_pos() else {
+        return;
+    };
+
+    let (x, z) = worley_image.world_position(normalized + 0.5);
+    let inspection = worley_res.get_worley().inspect(x, z);
+    let biome_label = |ui: &mut egui::Ui, biome: &BiomeT| {
+        let [r, g, b, _] = biome.get_color().to_u8_array();
+        ui.horizontal(|ui| {
+            let (swatch, _) = ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+            ui.painter()
+                .rect_filled(swatch, 2.0, egui::Color32::from_rgb(r, g, b));
+            ui.label(biome.debug_name());
+        });
+    };
+
+    egui::Area::new(egui::Id::new("worley_preview_tooltip"))
+        .order(egui::Order::Tooltip)
+        .fixed_pos(pointer + egui::vec2(16.0, 16.0))
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(format!("world x {x:.2}  z {z:.2}"));
+                ui.label(format!(
+                    "cell {}, {}  (warped {:.3}, {:.3})",
+                    inspection.cell.0,
+                    inspection.cell.1,
+                    inspection.position.0,
+                    inspection.position.1
+                ));
+                ui.separator();
+                egui::Grid::new("worley_preview_tooltip_weights").show(ui, |ui| {
+                    for (weight, biome) in inspection.weights.iter() {
+                        biome_label(ui, biome);
+                        ui.label(format!("{:.1}%", weight * 100.0));
+                        ui.end_row();
+                    }
+                });
+                ui.separator();
+                egui::Grid::new("worley_preview_tooltip_candidates")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for label in ["cell", "biome", "distance", "weight", ""] {
+                            ui.strong(label);
+                        }
+                        ui.end_row();
+                        for candidate in inspection.candidates.iter() {
+                            ui.label(format!("{}, {}", candidate.cell.0, candidate.cell.1));
+                            biome_label(ui, &candidate.biome);
+                            ui.label(format!("{:.4}", candidate.distance));
+                            ui.label(
+                                candidate
+                                    .weight
+                                    .map_or("-".to_string(), |w| format!("{:.1}%", w * 100.0)),
+                            );
+                            match candidate.fate {
+                                CandidateFate::Kept => ui.label(""),
+                                CandidateFate::BeyondK => ui.weak("beyond k"),
+                                CandidateFate::Killed => {
+                                    ui.colored_label(egui::Color32::LIGHT_RED, "killed")
+                                }
+                            };
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+}
+
 // tweaking ui for Worley
 fn tweak_ui<WorleyResT, BiomeT, Picker>(ui: &mut egui::Ui, world: &mut World)
 where
@@ -412,15 +889,36 @@ where
     let mut worley = ms.get_worley_mut();
 
     let mut any_changed = false;
+    // text typed for the world seed, kept by egui between frames
+    let world_seed_id = egui::Id::new("worley_world_seed");
+    let mut world_seed_text: String = ui
+        .data_mut(|data| data.get_temp(world_seed_id))
+        .unwrap_or_default();
+    ui.horizontal(|ui| {
+        ui.label("world seed");
+        if ui.text_edit_singleline(&mut world_seed_text).changed() {
+            worley.set_world_seed(WorldSeed::from_text(&world_seed_text));
+            any_changed = true;
+        }
+    });
+    ui.data_mut(|data| data.insert_temp(world_seed_id, world_seed_text));
     any_changed |= ui
-        .add(egui::Slider::new(&mut worley.seed, 0..=100).text("seed"))
+        .add(egui::DragValue::new(&mut worley.seed).prefix("seed: "))
         .changed();
+    let mut picker_seed = worley.picker_seed();
+    if ui
+        .add(egui::DragValue::new(&mut picker_seed).prefix("picker seed: "))
+        .changed()
+    {
+        worley.picker_seed = Some(picker_seed);
+        any_changed = true;
+    }
     any_changed |= ui
         .add(egui::Slider::new(&mut worley.sharpness, 0.5..=20.0).text("Sharpness"))
         .changed();
 
     any_changed |= ui
-        .add(egui::Slider::new(&mut worley.k, 1..=8).text("k (nearest)"))
+        .add(egui::Slider::new(&mut worley.k, 1..=MAX_K).text("k (nearest)"))
         .changed();
     any_changed |= ui
         .add(egui::Slider::new(&mut worley.zoom, 10.0..=200.0).text("Zoom"))
@@ -458,6 +956,21 @@ where
         s(&mut worley, &mut any_changed, DistanceFn::Hybrid);
     });
 
+    egui::CollapsingHeader::new("algorithm version").show(ui, |ui| {
+        for algorithm in AlgorithmVersion::ALL {
+            if ui
+                .add(egui::widgets::Button::selectable(
+                    worley.algorithm == algorithm,
+                    format!("{:?}", algorithm),
+                ))
+                .clicked()
+            {
+                worley.algorithm = algorithm;
+                any_changed = true;
+            }
+        }
+    });
+
     ui.group(|ui| {
         if ui
             .add(
@@ -468,92 +981,143 @@ where
         {
             any_changed = true;
         }
-        if ui
-            .add(
-                egui::Slider::new(&mut worley.warp_settings.noise.frequency, 0.0..=1.0)
-                    .text("Warp frequency"),
-            )
-            .changed()
-        {
-            any_changed = true;
-        }
-        if ui
-            .add(
-                egui::Slider::new(
-                    &mut worley.warp_settings.noise.fractal_lacunarity,
-                    0.0..=4.0,
-                )
-                .text("fractal lacunarity"),
-            )
-            .changed()
-        {
-            any_changed = true;
-        }
+        any_changed |= noise_ui(ui, &mut worley.warp_settings.noise);
+    });
 
-        let mut fractal_gain = worley.warp_settings.noise.get_fractal_gain();
-        if ui
-            .add(egui::Slider::new(&mut fractal_gain, 0.0..=3.0).text("fractal gain"))
-            .changed()
-        {
-            worley.warp_settings.noise.set_fractal_gain(fractal_gain);
-            any_changed = true;
+    if any_changed {
+        // trigger change to MapSettings, causing an update to voxels
+        map_settings.set_changed();
+    }
+}
+
+// lets the generic inspectors edit warp noises, registered by WorleyReflectPlugin
+impl InspectorPrimitive for Noise {
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        _options: &dyn std::any::Any,
+        _id: egui::Id,
+        _env: InspectorUi<'_, '_>,
+    ) -> bool {
+        let mut seed = self.seed();
+        let seed_changed = ui
+            .add(egui::DragValue::new(&mut seed).prefix("seed: "))
+            .changed();
+        if seed_changed {
+            self.set_seed(seed);
         }
-        if ui
-            .add(
-                egui::Slider::new(&mut worley.warp_settings.noise.fractal_octaves, 0..=5)
-                    .text("fractal octaves"),
-            )
-            .changed()
-        {
-            any_changed = true;
+        noise_ui(ui, self) || seed_changed
+    }
+
+    fn ui_readonly(
+        &self,
+        ui: &mut egui::Ui,
+        _options: &dyn std::any::Any,
+        _id: egui::Id,
+        _env: InspectorUi<'_, '_>,
+    ) {
+        let mut copy = self.clone();
+        ui.add_enabled_ui(false, |ui| noise_ui(ui, &mut copy));
+    }
+}
+
+// kind, shared fractal settings and FastNoise's own types of a warp noise,
+// also the inspector ui of Noise (see WorleyReflectPlugin)
+pub(crate) fn noise_ui(ui: &mut egui::Ui, noise: &mut Noise) -> bool {
+    let mut any_changed = false;
+    ui.label("warp noise");
+    ui.horizontal(|ui| {
+        for &kind in NoiseKind::BUILTIN {
+            if ui
+                .add(egui::widgets::Button::selectable(
+                    noise.kind() == kind,
+                    format!("{:?}", kind),
+                ))
+                .clicked()
+            {
+                noise.set_kind(kind);
+                any_changed = true;
+            }
         }
+    });
 
-        ui.label("warp noise");
+    let mut frequency = noise.frequency();
+    if ui
+        .add(egui::Slider::new(&mut frequency, 0.0..=1.0).text("Warp frequency"))
+        .changed()
+    {
+        noise.set_frequency(frequency);
+        any_changed = true;
+    }
+    let mut lacunarity = noise.lacunarity();
+    if ui
+        .add(egui::Slider::new(&mut lacunarity, 0.0..=4.0).text("fractal lacunarity"))
+        .changed()
+    {
+        noise.set_lacunarity(lacunarity);
+        any_changed = true;
+    }
+
+    let mut fractal_gain = noise.gain();
+    if ui
+        .add(egui::Slider::new(&mut fractal_gain, 0.0..=3.0).text("fractal gain"))
+        .changed()
+    {
+        noise.set_gain(fractal_gain);
+        any_changed = true;
+    }
+    let mut octaves = noise.octaves();
+    if ui
+        .add(egui::Slider::new(&mut octaves, 0..=5).text("fractal octaves"))
+        .changed()
+    {
+        noise.set_octaves(octaves);
+        any_changed = true;
+    }
+
+    // FastNoise has its own noise and fractal types
+    if let Noise::FastNoise(fast_noise) = noise {
         egui::CollapsingHeader::new("noise type").show(ui, |ui| {
-            let mut noise = |worley: &mut Worley<BiomeT, Picker>, noise_type: NoiseType| {
+            let mut noise = |fast_noise: &mut FastNoise, noise_type: NoiseType| {
                 if ui
                     .add(egui::widgets::Button::selectable(
-                        worley.warp_settings.noise.noise_type == noise_type,
+                        fast_noise.noise_type == noise_type,
                         format!("{:?}", noise_type),
                     ))
                     .clicked()
                 {
-                    worley.warp_settings.noise.noise_type = noise_type;
+                    fast_noise.noise_type = noise_type;
                     any_changed = true;
                 }
             };
-            noise(&mut worley, NoiseType::Value);
-            noise(&mut worley, NoiseType::ValueFractal);
-            noise(&mut worley, NoiseType::Perlin);
-            noise(&mut worley, NoiseType::PerlinFractal);
-            noise(&mut worley, NoiseType::Simplex);
-            noise(&mut worley, NoiseType::SimplexFractal);
-            noise(&mut worley, NoiseType::Cellular);
-            noise(&mut worley, NoiseType::WhiteNoise);
-            noise(&mut worley, NoiseType::Cubic);
-            noise(&mut worley, NoiseType::CubicFractal);
+            noise(fast_noise, NoiseType::Value);
+            noise(fast_noise, NoiseType::ValueFractal);
+            noise(fast_noise, NoiseType::Perlin);
+            noise(fast_noise, NoiseType::PerlinFractal);
+            noise(fast_noise, NoiseType::Simplex);
+            noise(fast_noise, NoiseType::SimplexFractal);
+            noise(fast_noise, NoiseType::Cellular);
+            noise(fast_noise, NoiseType::WhiteNoise);
+            noise(fast_noise, NoiseType::Cubic);
+            noise(fast_noise, NoiseType::CubicFractal);
         });
         egui::CollapsingHeader::new("fractal type").show(ui, |ui| {
-            let mut frac = |worley: &mut Worley<BiomeT, Picker>, fractal_type: FractalType| {
+            let mut frac = |fast_noise: &mut FastNoise, fractal_type: FractalType| {
                 if ui
                     .add(egui::widgets::Button::selectable(
-                        worley.warp_settings.noise.fractal_type == fractal_type,
+                        fast_noise.fractal_type == fractal_type,
                         format!("{:?}", fractal_type),
                     ))
                     .clicked()
                 {
-                    worley.warp_settings.noise.fractal_type = fractal_type;
+                    fast_noise.fractal_type = fractal_type;
                     any_changed = true;
                 }
             };
-            frac(&mut worley, FractalType::FBM);
-            frac(&mut worley, FractalType::Billow);
-            frac(&mut worley, FractalType::RigidMulti);
+            frac(fast_noise, FractalType::FBM);
+            frac(fast_noise, FractalType::Billow);
+            frac(fast_noise, FractalType::RigidMulti);
         });
-    });
-
-    if any_changed {
-        // trigger change to MapSettings, causing an update to voxels
-        map_settings.set_changed();
     }
+    any_changed
 }
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+use bevy::reflect::GetTypeRegistration;
+
+use std::marker::PhantomData;
+
+use crate::{
+    algorithm::AlgorithmVersion,
+    biome_picker::{BiomePicker, BiomeVariants},
+    distance_fn::DistanceFn,
+    noise::{Noise, NoiseKind, SimplexNoise, ValueNoise},
+    warp::WarpSettings,
+    worley::Worley,
+};
+
+use super::GetWorley;
+
+/// registers Worley and its settings for reflection, so a WorleyResT that derives Reflect
+/// (with `#[reflect(Resource)]`) shows up in bevy-inspector-egui's world / resource inspectors
+/// and in .scn.ron scenes. the biome enum and picker have to derive Reflect too.
+/// the warp noise is stored in scenes through serde, enable "serde" for that.
+/// Worley::from_reflect() rebuilds distance_fn, for edits applied to an existing worley
+/// the WorleyResT is synced after scenes spawn and at the end of the frame
+pub struct WorleyReflectPlugin<WorleyResT, BiomeT, Picker> {
+    pub _phantom: PhantomData<(WorleyResT, BiomeT, Picker)>,
+}
+
+impl<WorleyResT, BiomeT, Picker> Default for WorleyReflectPlugin<WorleyResT, BiomeT, Picker> {
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<WorleyResT, BiomeT, Picker> Plugin for WorleyReflectPlugin<WorleyResT, BiomeT, Picker>
+where
+    WorleyResT: Resource + GetWorley<BiomeT, Picker> + GetTypeRegistration,
+    BiomeT: BiomeVariants + GetTypeRegistration + Send + Sync + 'static,
+    Picker: BiomePicker<BiomeT> + Default + GetTypeRegistration + Send + Sync + 'static,
+    Worley<BiomeT, Picker>: GetTypeRegistration,
+{
+    fn build(&self, app: &mut App) {
+        app.register_type::<WorleyResT>()
+            .register_type::<Worley<BiomeT, Picker>>()
+            .register_type::<BiomeT>()
+            .register_type::<Picker>()
+            .register_type::<DistanceFn>()
+            .register_type::<AlgorithmVersion>()
+            .register_type::<WarpSettings>()
+            .register_type::<Noise>()
+            .register_type::<NoiseKind>()
+            .register_type::<ValueNoise>()
+            .register_type::<SimplexNoise>()
+            .add_systems(
+                SpawnScene,
+                sync_distance_fn::<WorleyResT, BiomeT, Picker>
+                    .after(bevy::scene::scene_spawner_system),
+            )
+            .add_systems(Last, sync_distance_fn::<WorleyResT, BiomeT, Picker>);
+        #[cfg(feature = "bevy_debug")]
+        app.register_type_data::<Noise, bevy_inspector_egui::inspector_egui_impls::InspectorEguiImpl>();
+    }
+}
+
+// inspectors and scenes applied over the resource only edit distance_fn_config
+fn sync_distance_fn<WorleyResT, BiomeT, Picker>(mut worley_res: ResMut<WorleyResT>)
+where
+    WorleyResT: Resource + GetWorley<BiomeT, Picker>,
+    BiomeT: BiomeVariants,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    if !worley_res.is_changed() {
+        return;
+    }
+    let worley = worley_res.bypass_change_detection().get_worley_mut();
+    worley.distance_fn = worley.distance_fn_config.to_func();
+}
@@ -0,0 +1,146 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use std::marker::PhantomData;
+
+use tinyvec::TinyVec;
+
+use crate::{
+    biome_picker::{BiomePicker, BiomeVariants},
+    blend::BiomeBlend,
+    worley::Worley,
+};
+
+use super::GetWorley;
+
+/// gameplay side of worley biomes, no egui or DebugColor needed:
+/// keeps BiomeInfluence up to date and sends BiomeEntered / BiomeExited messages.
+/// sample anywhere with the BiomeSampler SystemParam.
+/// worlds are sampled on the XZ plane, y is ignored
+pub struct WorleyBiomesPlugin<WorleyResT, BiomeT, Picker> {
+    pub _phantom: PhantomData<(WorleyResT, BiomeT, Picker)>,
+}
+
+impl<WorleyResT, BiomeT, Picker> Default for WorleyBiomesPlugin<WorleyResT, BiomeT, Picker> {
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<WorleyResT, BiomeT, Picker> Plugin for WorleyBiomesPlugin<WorleyResT, BiomeT, Picker>
+where
+    WorleyResT: Resource + GetWorley<BiomeT, Picker>,
+    BiomeT: BiomeVariants + PartialEq + Default + Send + Sync + 'static,
+    Picker: BiomePicker<BiomeT> + Default + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        app.add_message::<BiomeEntered<BiomeT>>()
+            .add_message::<BiomeExited<BiomeT>>()
+            .add_systems(
+                PostUpdate,
+                update_biome_influence::<WorleyResT, BiomeT, Picker>
+                    .after(TransformSystems::Propagate),
+            );
+    }
+}
+
+/// read access to the worley of WorleyResT, sampled at world positions
+#[derive(SystemParam)]
+pub struct BiomeSampler<'w, WorleyResT, BiomeT, Picker>
+where
+    WorleyResT: Resource + GetWorley<BiomeT, Picker>,
+    BiomeT: BiomeVariants + Default + 'static,
+    Picker: BiomePicker<BiomeT> + Default + 'static,
+{
+    worley_res: Res<'w, WorleyResT>,
+    _phantom: PhantomData<fn() -> (BiomeT, Picker)>,
+}
+
+impl<WorleyResT, BiomeT, Picker> BiomeSampler<'_, WorleyResT, BiomeT, Picker>
+where
+    WorleyResT: Resource + GetWorley<BiomeT, Picker>,
+    BiomeT: BiomeVariants + Default + 'static,
+    Picker: BiomePicker<BiomeT> + Default + 'static,
+{
+    pub fn worley(&self) -> &Worley<BiomeT, Picker> {
+        self.worley_res.get_worley()
+    }
+
+    /// biome weights at (position), see Worley::get()
+    pub fn weights(&self, position: Vec3) -> TinyVec<[(f64, BiomeT); 3]> {
+        self.worley().get(position.x as f64, position.z as f64)
+    }
+
+    /// strongest biome at (position)
+    pub fn dominant(&self, position: Vec3) -> Option<BiomeT> {
+        self.weights(position).dominant()
+    }
+
+    /// one biome per block, see Worley::pick_dithered()
+    pub fn pick_dithered(&self, position: Vec3) -> Option<BiomeT> {
+        self.worley()
+            .pick_dithered(position.x as f64, position.z as f64)
+    }
+
+    /// true when the worley changed since the system last ran
+    pub fn is_changed(&self) -> bool {
+        self.worley_res.is_changed()
+    }
+}
+
+/// biome weights at an entity's GlobalTransform, add it to entities that need them.
+/// updated when the entity moves or the worley changes
+#[derive(Component, Debug, Clone, Default)]
+pub struct BiomeInfluence<BiomeT: Default> {
+    pub weights: TinyVec<[(f64, BiomeT); 3]>,
+    /// strongest biome of weights
+    pub dominant: Option<BiomeT>,
+}
+
+/// the dominant biome of (entity) became (biome)
+#[derive(Message, Debug, Clone)]
+pub struct BiomeEntered<BiomeT> {
+    pub entity: Entity,
+    pub biome: BiomeT,
+}
+
+/// (biome) stopped being the dominant biome of (entity)
+#[derive(Message, Debug, Clone)]
+pub struct BiomeExited<BiomeT> {
+    pub entity: Entity,
+    pub biome: BiomeT,
+}
+
+fn update_biome_influence<WorleyResT, BiomeT, Picker>(
+    worley_res: Res<WorleyResT>,
+    mut query: Query<(Entity, Ref<GlobalTransform>, &mut BiomeInfluence<BiomeT>)>,
+    mut entered: MessageWriter<BiomeEntered<BiomeT>>,
+    mut exited: MessageWriter<BiomeExited<BiomeT>>,
+) where
+    WorleyResT: Resource + GetWorley<BiomeT, Picker>,
+    BiomeT: BiomeVariants + PartialEq + Default + Send + Sync + 'static,
+    Picker: BiomePicker<BiomeT> + Default + Send + Sync + 'static,
+{
+    let worley_changed = worley_res.is_changed();
+    let worley = worley_res.get_worley();
+    for (entity, transform, mut influence) in query.iter_mut() {
+        if !worley_changed && !transform.is_changed() && !influence.is_added() {
+            continue;
+        }
+        let position = transform.translation();
+        let weights = worley.get(position.x as f64, position.z as f64);
+        let dominant = weights.dominant();
+        if dominant != influence.dominant {
+            if let Some(biome) = influence.dominant {
+                exited.write(BiomeExited { entity, biome });
+            }
+            if let Some(biome) = dominant {
+                entered.write(BiomeEntered { entity, biome });
+            }
+        }
+        influence.weights = weights;
+        influence.dominant = dominant;
+    }
+}
@@ -0,0 +1,46 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{
+        Extent3d, TextureDimension, TextureFormat, TextureViewDescriptor, TextureViewDimension,
+    },
+};
+
+use crate::splat::SplatMap;
+
+/// one layer of (splat) as an Image, add it to Assets<Image> for your terrain material.
+/// the format is linear Rgba8Unorm, weights aren't colors
+pub fn splat_image(splat: &SplatMap, layer: usize) -> Image {
+    Image::new(
+        extent(splat, 1),
+        TextureDimension::D2,
+        splat.layers[layer].clone(),
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    )
+}
+
+/// every layer of (splat) as one 2d texture array, array layer i is SplatMap::layers[i]
+pub fn splat_array_image(splat: &SplatMap) -> Image {
+    let mut image = Image::new(
+        extent(splat, splat.layers.len() as u32),
+        TextureDimension::D2,
+        splat.array_data(),
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    );
+    // a single layer would default to a plain 2d view
+    image.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::D2Array),
+        ..default()
+    });
+    image
+}
+
+fn extent(splat: &SplatMap, layers: u32) -> Extent3d {
+    Extent3d {
+        width: splat.width as u32,
+        height: splat.depth as u32,
+        depth_or_array_layers: layers,
+    }
+}
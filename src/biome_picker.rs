@@ -1,11 +1,39 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "bevy")]
+use bevy::reflect::Reflect;
+#[cfg(all(feature = "bevy", feature = "serde"))]
+use bevy::reflect::{ReflectDeserialize, ReflectSerialize};
 use rand::Rng;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::utils::{hash_u64, seeded_rng};
+use crate::error::WorleyError;
+use crate::hash::HashVersion;
 
 pub trait BiomePicker<BiomeT> {
     fn pick_biome(&self, seed: u64, cell_x: i32, cell_z: i32) -> BiomeT;
+
+    /// what Worley calls, (hash) is the Worley's hash version. override it and use (hash)
+    /// for every hash or rng so the picks stay portable when the Worley is
+    fn pick_biome_versioned(
+        &self,
+        seed: u64,
+        cell_x: i32,
+        cell_z: i32,
+        _hash: HashVersion,
+    ) -> BiomeT {
+        self.pick_biome(seed, cell_x, cell_z)
+    }
+
+    /// error if pick_biome() would panic or be biased, called by Worley::validate()
+    fn validate(&self) -> Result<(), WorleyError> {
+        Ok(())
+    }
+
+    /// fix odds that don't add up, called when loading configs with `normalize_weights: true`
+    fn normalize(&mut self) {}
 }
 
 ///! trait needed to know what variants are available
@@ -15,10 +43,12 @@ pub trait BiomeVariants: Copy {
 
 ///! used to generates a biome VARIANT, based upon a "cell" position
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[derive(Default, Clone)]
 pub enum SimpleBiomePicker<BiomeT: BiomeVariants> {
     // all variants have same chance of being selected
     #[default]
+    #[cfg_attr(feature = "serde", serde(alias = "UniformDistribution"))]
     Any,
     // all variants have same chance of being selected
     AnyOf(Vec<BiomeT>),
@@ -26,23 +56,46 @@ pub enum SimpleBiomePicker<BiomeT: BiomeVariants> {
     Weighted(Vec<(BiomeT, f32)>),
 }
 
-impl<BiomeT: BiomeVariants + 'static> BiomePicker<BiomeT> for SimpleBiomePicker<BiomeT> {
+// empty lists fail validate(), sampling them anyway gives BiomeT::default() instead of panicking
+impl<BiomeT: BiomeVariants + Default + 'static> BiomePicker<BiomeT> for SimpleBiomePicker<BiomeT> {
+    /// same as pick_biome_versioned() with HashVersion::V0
     #[inline(always)]
     fn pick_biome(&self, seed: u64, cell_x: i32, cell_z: i32) -> BiomeT {
+        self.pick_biome_versioned(seed, cell_x, cell_z, HashVersion::V0)
+    }
+
+    #[inline(always)]
+    fn pick_biome_versioned(
+        &self,
+        seed: u64,
+        cell_x: i32,
+        cell_z: i32,
+        hash: HashVersion,
+    ) -> BiomeT {
         match self {
             SimpleBiomePicker::Any => {
                 let variants = BiomeT::variants();
-                let idx = (hash_u64(seed, cell_x, cell_z) % variants.len() as u64) as usize;
+                if variants.is_empty() {
+                    return BiomeT::default();
+                }
+                let idx = (hash.hash(seed, cell_x, cell_z) % variants.len() as u64) as usize;
                 variants[idx]
             }
             SimpleBiomePicker::AnyOf(variants) => {
-                let idx = (hash_u64(seed, cell_x, cell_z) % variants.len() as u64) as usize;
+                if variants.is_empty() {
+                    return BiomeT::default();
+                }
+                let idx = (hash.hash(seed, cell_x, cell_z) % variants.len() as u64) as usize;
                 variants[idx]
             }
             SimpleBiomePicker::Weighted(weights) => {
-                // turn hash into rng
-                let mut rng = seeded_rng(seed, cell_x, cell_z);
-                let roll: f32 = rng.random();
+                let roll: f32 = match hash {
+                    // turn hash into rng
+                    HashVersion::V0 => hash.rng(seed, cell_x, cell_z).random(),
+                    // no rng needed, rand's float conversion isn't promised to be stable.
+                    // top 24 bits, every value exact in 0.0..1.0
+                    HashVersion::V1 => (hash.hash(seed, cell_x, cell_z) >> 40) as f32 / 16777216.0,
+                };
 
                 let mut cumulative = 0.0;
                 for (biome, weight) in weights {
@@ -52,10 +105,202 @@ impl<BiomeT: BiomeVariants + 'static> BiomePicker<BiomeT> for SimpleBiomePicker<
                     }
                 }
                 // fallback (shouldn’t happen if weights sum to 1.0)
-                weights.last().unwrap().0
+                weights
+                    .last()
+                    .map_or_else(BiomeT::default, |(biome, _)| *biome)
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), WorleyError> {
+        match self {
+            SimpleBiomePicker::Any if BiomeT::variants().is_empty() => Err(WorleyError::NoVariants),
+            SimpleBiomePicker::Any => Ok(()),
+            SimpleBiomePicker::AnyOf(variants) if variants.is_empty() => {
+                Err(WorleyError::EmptyAnyOf)
+            }
+            SimpleBiomePicker::AnyOf(_) => Ok(()),
+            SimpleBiomePicker::Weighted(weights) => {
+                if weights.is_empty() {
+                    return Err(WorleyError::EmptyWeights);
+                }
+                for (index, (_, weight)) in weights.iter().enumerate() {
+                    if !weight.is_finite() || *weight < 0.0 {
+                        return Err(WorleyError::InvalidWeight {
+                            index,
+                            weight: *weight,
+                        });
+                    }
+                }
+                let sum: f32 = weights.iter().map(|(_, weight)| weight).sum();
+                if (sum - 1.0).abs() > WEIGHT_SUM_TOLERANCE {
+                    return Err(WorleyError::WeightSum(sum));
+                }
+                Ok(())
             }
         }
     }
+
+    /// scale Weighted weights to sum to 1.0, keeping their ratios
+    fn normalize(&mut self) {
+        if let SimpleBiomePicker::Weighted(weights) = self {
+            let sum: f32 = weights.iter().map(|(_, weight)| weight).sum();
+            if sum > 0.0 && sum.is_finite() {
+                for (_, weight) in weights.iter_mut() {
+                    *weight /= sum;
+                }
+            }
+        }
+    }
+}
+
+// f32 weights written by hand (0.33, 0.33, 0.34) rarely add up exactly
+const WEIGHT_SUM_TOLERANCE: f32 = 1e-3;
+
+/// weighted odds like SimpleBiomePicker::Weighted, precomputed into a (Vose) alias table.
+/// picking is O(1) and uses ONE HashVersion::hash() per cell instead of building an rng,
+/// weights don't have to add up to 1.0.
+/// the pick for a cell is fully determined by (seed, cell, weights):
+/// column = (high 32 bits of HashVersion::hash(seed, cell) * biome count) >> 32,
+/// the column's own biome is kept when (low 32 bits / 2^32) < its probability, else its alias.
+/// NOT the same picks as SimpleBiomePicker::Weighted for the same weights
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(
+        try_from = "Vec<(BiomeT, f32)>",
+        into = "Vec<(BiomeT, f32)>",
+        bound(
+            serialize = "BiomeT: Serialize",
+            deserialize = "BiomeT: Deserialize<'de>"
+        )
+    )
+)]
+// opaque, editing the weights has to rebuild the tables
+#[cfg_attr(feature = "bevy", derive(Reflect), reflect(opaque, Clone))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize, where BiomeT: Serialize + for<'de> Deserialize<'de>)
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliasBiomePicker<BiomeT: BiomeVariants> {
+    weights: Vec<(BiomeT, f32)>,
+    // chance to keep the column's own biome, instead of its alias
+    probability: Vec<f64>,
+    alias: Vec<u32>,
+}
+
+impl<BiomeT: BiomeVariants> Default for AliasBiomePicker<BiomeT> {
+    fn default() -> Self {
+        Self {
+            weights: Vec::new(),
+            probability: Vec::new(),
+            alias: Vec::new(),
+        }
+    }
+}
+
+impl<BiomeT: BiomeVariants> AliasBiomePicker<BiomeT> {
+    pub fn new(weights: Vec<(BiomeT, f32)>) -> Result<Self, WorleyError> {
+        if weights.is_empty() {
+            return Err(WorleyError::EmptyWeights);
+        }
+        for (index, (_, weight)) in weights.iter().enumerate() {
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(WorleyError::InvalidWeight {
+                    index,
+                    weight: *weight,
+                });
+            }
+        }
+        let sum: f64 = weights.iter().map(|(_, weight)| *weight as f64).sum();
+        if sum <= 0.0 {
+            return Err(WorleyError::WeightSum(sum as f32));
+        }
+
+        // Vose's alias method
+        let n = weights.len();
+        let mut scaled: Vec<f64> = weights
+            .iter()
+            .map(|(_, weight)| *weight as f64 * n as f64 / sum)
+            .collect();
+        let mut probability = vec![1.0; n];
+        let mut alias: Vec<u32> = (0..n as u32).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..n).partition(|i| scaled[*i] < 1.0);
+        while let (Some(s), Some(&l)) = (small.pop(), large.last()) {
+            probability[s] = scaled[s];
+            alias[s] = l as u32;
+            scaled[l] -= 1.0 - scaled[s];
+            if scaled[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // leftovers are 1.0, up to float error
+
+        Ok(Self {
+            weights,
+            probability,
+            alias,
+        })
+    }
+
+    /// the weights this table was built from
+    pub fn weights(&self) -> &[(BiomeT, f32)] {
+        &self.weights
+    }
+}
+
+// the default picker has no table and fails validate(), sampling it anyway gives BiomeT::default()
+impl<BiomeT: BiomeVariants + Default + 'static> BiomePicker<BiomeT> for AliasBiomePicker<BiomeT> {
+    /// same as pick_biome_versioned() with HashVersion::V0
+    #[inline(always)]
+    fn pick_biome(&self, seed: u64, cell_x: i32, cell_z: i32) -> BiomeT {
+        self.pick_biome_versioned(seed, cell_x, cell_z, HashVersion::V0)
+    }
+
+    #[inline(always)]
+    fn pick_biome_versioned(
+        &self,
+        seed: u64,
+        cell_x: i32,
+        cell_z: i32,
+        hash: HashVersion,
+    ) -> BiomeT {
+        if self.weights.is_empty() {
+            return BiomeT::default();
+        }
+        let hash = hash.hash(seed, cell_x, cell_z);
+        let column = (((hash >> 32) * self.weights.len() as u64) >> 32) as usize;
+        let coin = (hash & 0xFFFF_FFFF) as f64 / 4294967296.0;
+        if coin < self.probability[column] {
+            self.weights[column].0
+        } else {
+            self.weights[self.alias[column] as usize].0
+        }
+    }
+
+    fn validate(&self) -> Result<(), WorleyError> {
+        if self.weights.is_empty() {
+            return Err(WorleyError::EmptyWeights);
+        }
+        Ok(())
+    }
+}
+
+impl<BiomeT: BiomeVariants> TryFrom<Vec<(BiomeT, f32)>> for AliasBiomePicker<BiomeT> {
+    type Error = WorleyError;
+
+    fn try_from(weights: Vec<(BiomeT, f32)>) -> Result<Self, Self::Error> {
+        Self::new(weights)
+    }
+}
+
+impl<BiomeT: BiomeVariants> From<AliasBiomePicker<BiomeT>> for Vec<(BiomeT, f32)> {
+    fn from(picker: AliasBiomePicker<BiomeT>) -> Self {
+        picker.weights
+    }
 }
 
 // impl<BiomeT: Biome + 'static> SimpleBiomePicker<BiomeT> {
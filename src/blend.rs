@@ -0,0 +1,131 @@
+//! blend per biome values using the (weight, biome) pairs returned by Worley::get()
+//!
+//! ```ignore
+//! let weights = worley.get(x, z);
+//! let height: Option<f32> = weights.blend(|biome| biome.height());
+//! let block = weights.pick_by_weight(roll).map(|biome| biome.top_block());
+//! ```
+
+/// a value that can be linearly interpolated, used to blend values across biome borders
+pub trait Lerp {
+    /// t = 0.0 returns self, t = 1.0 returns other
+    fn lerp(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Lerp for f32 {
+    #[inline(always)]
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t as f32
+    }
+}
+
+impl Lerp for f64 {
+    #[inline(always)]
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl<T: Lerp, const N: usize> Lerp for [T; N] {
+    #[inline(always)]
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        core::array::from_fn(|i| self[i].lerp(&other[i], t))
+    }
+}
+
+impl<A: Lerp, B: Lerp> Lerp for (A, B) {
+    #[inline(always)]
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        (self.0.lerp(&other.0, t), self.1.lerp(&other.1, t))
+    }
+}
+
+impl<A: Lerp, B: Lerp, C: Lerp> Lerp for (A, B, C) {
+    #[inline(always)]
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        (
+            self.0.lerp(&other.0, t),
+            self.1.lerp(&other.1, t),
+            self.2.lerp(&other.2, t),
+        )
+    }
+}
+
+// bevy colors blend with their own Mix implementation
+#[cfg(feature = "bevy")]
+macro_rules! impl_lerp_for_mix {
+    ($($color:ty),*) => {
+        $(
+            impl Lerp for $color {
+                #[inline(always)]
+                fn lerp(&self, other: &Self, t: f64) -> Self {
+                    bevy::color::Mix::mix(self, other, t as f32)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "bevy")]
+impl_lerp_for_mix!(
+    bevy::color::Color,
+    bevy::color::Srgba,
+    bevy::color::LinearRgba,
+    bevy::color::Oklaba
+);
+
+/// blending helpers for the (weight, biome) pairs returned by Worley::get()
+pub trait BiomeBlend<BiomeT> {
+    /// weighted average of a value per biome, None if there are no biomes
+    fn blend<V: Lerp>(&self, value: impl Fn(&BiomeT) -> V) -> Option<V>;
+
+    /// pick ONE biome where the chance of being picked equals its weight.
+    /// roll: 0.0..=1.0, use a deterministic roll (hash or dither pattern) for reproducible results
+    /// useful for discrete properties like block types, that can't be blended.
+    /// biomes with a weight of 0.0 are never picked, unless every weight is 0.0
+    fn pick_by_weight(&self, roll: f64) -> Option<BiomeT>;
+
+    /// the biome with the highest weight, the first one on ties
+    fn dominant(&self) -> Option<BiomeT>;
+}
+
+impl<BiomeT: Copy> BiomeBlend<BiomeT> for [(f64, BiomeT)] {
+    fn blend<V: Lerp>(&self, value: impl Fn(&BiomeT) -> V) -> Option<V> {
+        let mut iter = self.iter();
+        let (first_weight, first_biome) = iter.next()?;
+        let mut blended = value(first_biome);
+        let mut weight_sum = *first_weight;
+        // running weighted average, only requires lerp
+        for (weight, biome) in iter {
+            weight_sum += weight;
+            if weight_sum > 0.0 {
+                blended = blended.lerp(&value(biome), weight / weight_sum);
+            }
+        }
+        Some(blended)
+    }
+
+    fn pick_by_weight(&self, roll: f64) -> Option<BiomeT> {
+        let weight_sum: f64 = self.iter().map(|(weight, _biome)| weight).sum();
+        let target = roll * weight_sum;
+        let mut cumulative = 0.0;
+        for (weight, biome) in self {
+            cumulative += weight;
+            if target < cumulative {
+                return Some(*biome);
+            }
+        }
+        // roll of 1.0 or float rounding, the last biome that can be picked
+        self.iter()
+            .rev()
+            .find(|(weight, _biome)| *weight > 0.0)
+            .or(self.last())
+            .map(|(_weight, biome)| *biome)
+    }
+
+    fn dominant(&self) -> Option<BiomeT> {
+        self.iter()
+            .reduce(|best, next| if next.0 > best.0 { next } else { best })
+            .map(|(_weight, biome)| *biome)
+    }
+}
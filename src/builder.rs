@@ -0,0 +1,234 @@
+#[cfg(feature = "serde")]
+use std::path::Path;
+
+#[cfg(feature = "fast-noise")]
+use bracket_fast_noise::prelude::{FractalType, NoiseType};
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+use crate::algorithm::AlgorithmVersion;
+use crate::biome_picker::{BiomePicker, BiomeVariants};
+#[cfg(feature = "serde")]
+use crate::config::{ConfigError, WorleyRepr, read_config};
+use crate::distance_fn::DistanceFn;
+use crate::error::WorleyError;
+use crate::noise::Noise;
+#[cfg(feature = "fast-noise")]
+use crate::noise::NoiseKind;
+use crate::seed::WorldSeed;
+use crate::warp::WarpSettings;
+use crate::worley::Worley;
+
+/// step by step Worley setup, starting from Worley::default() or a config file.
+/// ```ignore
+/// let worley = Worley::<MyBiome, SimpleBiomePicker<MyBiome>>::builder()
+///     .zoom(62.0)
+///     .distance_fn(DistanceFn::Chebyshev)
+///     .warp_strength(0.6)
+///     .noise_frequency(0.7)
+///     .build()?;
+/// ```
+pub struct WorleyBuilder<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    worley: Worley<BiomeT, Picker>,
+    // first setting the warp noise couldn't take, returned by build()
+    noise_error: Option<WorleyError>,
+}
+
+impl<BiomeT, Picker> Default for WorleyBuilder<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    fn default() -> Self {
+        Self {
+            worley: Worley::default(),
+            noise_error: None,
+        }
+    }
+}
+
+impl<BiomeT, Picker> Worley<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    pub fn builder() -> WorleyBuilder<BiomeT, Picker> {
+        WorleyBuilder::default()
+    }
+}
+
+impl<BiomeT, Picker> WorleyBuilder<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// continue from an existing worley
+    pub fn from_worley(worley: Worley<BiomeT, Picker>) -> Self {
+        Self {
+            worley,
+            noise_error: None,
+        }
+    }
+
+    pub fn biome_picker(mut self, biome_picker: Picker) -> Self {
+        self.worley.biome_picker = biome_picker;
+        self
+    }
+
+    pub fn zoom(mut self, zoom: f64) -> Self {
+        self.worley.zoom = zoom;
+        self
+    }
+
+    /// also keeps Worley::distance_fn in sync
+    pub fn distance_fn(mut self, distance_fn: DistanceFn) -> Self {
+        self.worley.distance_fn = distance_fn.to_func();
+        self.worley.distance_fn_config = distance_fn;
+        self
+    }
+
+    pub fn sharpness(mut self, sharpness: f64) -> Self {
+        self.worley.sharpness = sharpness;
+        self
+    }
+
+    pub fn k(mut self, k: usize) -> Self {
+        self.worley.k = k;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.worley.seed = seed;
+        self
+    }
+
+    pub fn picker_seed(mut self, seed: u64) -> Self {
+        self.worley.picker_seed = Some(seed);
+        self
+    }
+
+    /// every layer seeded from one seed, see Worley::set_world_seed().
+    /// takes a WorldSeed, u64 or text: .world_seed("mossy hills")
+    pub fn world_seed(mut self, world_seed: impl Into<WorldSeed>) -> Self {
+        self.worley.set_world_seed(world_seed.into());
+        self
+    }
+
+    pub fn algorithm(mut self, algorithm: AlgorithmVersion) -> Self {
+        self.worley.algorithm = algorithm;
+        self
+    }
+
+    pub fn kill_percent_threshold(mut self, threshold: Option<f64>) -> Self {
+        self.worley.kill_percent_threshold = threshold;
+        self
+    }
+
+    pub fn warp_settings(mut self, warp_settings: WarpSettings) -> Self {
+        self.worley.warp_settings = warp_settings;
+        self
+    }
+
+    pub fn warp_strength(mut self, strength: f32) -> Self {
+        self.worley.warp_settings.strength = strength;
+        self
+    }
+
+    /// any builtin noise, or your own with Noise::custom()
+    pub fn warp_noise(mut self, noise: impl Into<Noise>) -> Self {
+        self.worley.warp_settings.noise = noise.into();
+        self
+    }
+
+    pub fn noise_seed(mut self, seed: u64) -> Self {
+        self.worley.warp_settings.noise.set_seed(seed);
+        self
+    }
+
+    pub fn noise_frequency(mut self, frequency: f32) -> Self {
+        self.worley.warp_settings.noise.set_frequency(frequency);
+        self
+    }
+
+    /// FastNoise only, build() fails with UnsupportedNoiseSetting for other warp noises
+    #[cfg(feature = "fast-noise")]
+    pub fn noise_type(mut self, noise_type: NoiseType) -> Self {
+        match &mut self.worley.warp_settings.noise {
+            Noise::FastNoise(noise) => noise.set_noise_type(noise_type),
+            noise => {
+                let kind = noise.kind();
+                self.unsupported_noise_setting("noise_type", kind);
+            }
+        }
+        self
+    }
+
+    /// FastNoise only, build() fails with UnsupportedNoiseSetting for other warp noises
+    #[cfg(feature = "fast-noise")]
+    pub fn fractal_type(mut self, fractal_type: FractalType) -> Self {
+        match &mut self.worley.warp_settings.noise {
+            Noise::FastNoise(noise) => noise.set_fractal_type(fractal_type),
+            noise => {
+                let kind = noise.kind();
+                self.unsupported_noise_setting("fractal_type", kind);
+            }
+        }
+        self
+    }
+
+    pub fn fractal_octaves(mut self, octaves: u32) -> Self {
+        self.worley.warp_settings.noise.set_octaves(octaves);
+        self
+    }
+
+    pub fn fractal_lacunarity(mut self, lacunarity: f32) -> Self {
+        self.worley.warp_settings.noise.set_lacunarity(lacunarity);
+        self
+    }
+
+    pub fn fractal_gain(mut self, gain: f32) -> Self {
+        self.worley.warp_settings.noise.set_gain(gain);
+        self
+    }
+
+    #[cfg(feature = "fast-noise")]
+    fn unsupported_noise_setting(&mut self, setting: &'static str, kind: NoiseKind) {
+        self.noise_error
+            .get_or_insert(WorleyError::UnsupportedNoiseSetting { setting, kind });
+    }
+
+    /// validated Worley, see Worley::validate()
+    pub fn build(self) -> Result<Worley<BiomeT, Picker>, WorleyError> {
+        if let Some(err) = self.noise_error {
+            return Err(err);
+        }
+        self.worley.validate()?;
+        Ok(self.worley)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<BiomeT, Picker> WorleyBuilder<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants + for<'de> Deserialize<'de>,
+    Picker: BiomePicker<BiomeT> + Default + for<'de> Deserialize<'de>,
+{
+    /// start from a .worley.ron of any known version, validated on build().
+    /// build() errors convert into ConfigError::Invalid, the same error Worley::from_ron() returns
+    pub fn from_ron(ron: &str) -> Result<Self, ConfigError> {
+        Ok(Self::from_worley(WorleyRepr::parse(ron)?.into_worley()?))
+    }
+
+    /// same as from_ron(), reading the file at (path)
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::from_ron(&read_config(path.as_ref())?)
+    }
+}
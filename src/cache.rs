@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hash};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tinyvec::TinyVec;
+
+use crate::biome_picker::{BiomePicker, BiomeVariants};
+use crate::region::{Region, SampleGrid};
+use crate::utils::FxHasher;
+use crate::worley::Worley;
+
+/// memory bounds for CachedWorley
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CacheSettings {
+    /// how many cells (feature point + biome) to remember, 0 disables the cell cache
+    pub cell_capacity: usize,
+    /// how many chunk sample grids to remember, 0 disables the chunk cache
+    pub chunk_capacity: usize,
+    /// world size of a chunk
+    pub chunk_size: f64,
+    /// samples per chunk axis
+    pub chunk_resolution: usize,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            cell_capacity: 4096,
+            chunk_capacity: 64,
+            chunk_size: 32.0,
+            chunk_resolution: 32,
+        }
+    }
+}
+
+/// hit/miss counters since creation (or the last CachedWorley::clear())
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub cell_hits: u64,
+    pub cell_misses: u64,
+    pub chunk_hits: u64,
+    pub chunk_misses: u64,
+    pub evictions: u64,
+}
+
+// (cell_x, cell_z) -> (feature point, biome)
+type CellCache<BiomeT> = LruCache<(i32, i32), ((f64, f64), BiomeT)>;
+// (chunk_x, chunk_z) -> samples
+type ChunkCache<BiomeT> = LruCache<(i32, i32), Arc<SampleGrid<BiomeT>>>;
+
+/// wraps a Worley, and remembers cell lookups and chunk sample grids
+/// for workloads sampling the same area over and over (meshing, decoration, lighting).
+/// results are identical to the wrapped Worley.
+/// safe to share between threads
+pub struct CachedWorley<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants + Default,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    worley: Worley<BiomeT, Picker>,
+    settings: CacheSettings,
+    cells: Mutex<CellCache<BiomeT>>,
+    chunks: Mutex<ChunkCache<BiomeT>>,
+    cell_hits: AtomicU64,
+    cell_misses: AtomicU64,
+    chunk_hits: AtomicU64,
+    chunk_misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl<BiomeT, Picker> CachedWorley<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants + 'static + Default,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    pub fn new(worley: Worley<BiomeT, Picker>, settings: CacheSettings) -> Self {
+        Self {
+            worley,
+            cells: Mutex::new(LruCache::new(settings.cell_capacity)),
+            chunks: Mutex::new(LruCache::new(settings.chunk_capacity)),
+            settings,
+            cell_hits: AtomicU64::new(0),
+            cell_misses: AtomicU64::new(0),
+            chunk_hits: AtomicU64::new(0),
+            chunk_misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    pub fn worley(&self) -> &Worley<BiomeT, Picker> {
+        &self.worley
+    }
+
+    /// change the worley, cached values are dropped since they no longer match
+    pub fn worley_mut(&mut self) -> &mut Worley<BiomeT, Picker> {
+        self.clear();
+        &mut self.worley
+    }
+
+    pub fn into_inner(self) -> Worley<BiomeT, Picker> {
+        self.worley
+    }
+
+    pub fn settings(&self) -> &CacheSettings {
+        &self.settings
+    }
+
+    /// same as Worley::get()
+    pub fn get(&self, x: f64, z: f64) -> TinyVec<[(f64, BiomeT); 3]> {
+        if self.settings.cell_capacity == 0 {
+            return self.worley.get(x, z);
+        }
+        let (mut hits, mut misses, mut evictions) = (0, 0, 0);
+        // lock per lookup and insert, other threads can use the cache while a cell is hashed
+        let cell = |cell_x, cell_z| {
+            let cached = self
+                .cells
+                .lock()
+                .expect("cell cache lock")
+                .get(&(cell_x, cell_z))
+                .copied();
+            if let Some(cell) = cached {
+                hits += 1;
+                return cell;
+            }
+            misses += 1;
+            let cell = self.worley.cell(cell_x, cell_z);
+            if self
+                .cells
+                .lock()
+                .expect("cell cache lock")
+                .insert((cell_x, cell_z), cell)
+            {
+                evictions += 1;
+            }
+            cell
+        };
+        let weights = self.worley.get_with_cells(x, z, cell, &mut ());
+        self.cell_hits.fetch_add(hits, Ordering::Relaxed);
+        self.cell_misses.fetch_add(misses, Ordering::Relaxed);
+        self.evictions.fetch_add(evictions, Ordering::Relaxed);
+        weights
+    }
+
+    /// sample grid of chunk (chunk_x, chunk_z), see CacheSettings for size and resolution
+    pub fn chunk(&self, chunk_x: i32, chunk_z: i32) -> Arc<SampleGrid<BiomeT>> {
+        if let Some(grid) = self
+            .chunks
+            .lock()
+            .expect("chunk cache lock")
+            .get(&(chunk_x, chunk_z))
+        {
+            self.chunk_hits.fetch_add(1, Ordering::Relaxed);
+            return grid.clone();
+        }
+        self.chunk_misses.fetch_add(1, Ordering::Relaxed);
+
+        // sample without holding the lock, other threads can keep reading
+        let region = Region::chunk(chunk_x, chunk_z, self.settings.chunk_size);
+        let resolution = self.settings.chunk_resolution;
+        let grid = Arc::new(self.worley.sample_region(region, resolution, resolution));
+
+        if self
+            .chunks
+            .lock()
+            .expect("chunk cache lock")
+            .insert((chunk_x, chunk_z), grid.clone())
+        {
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+        grid
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            cell_hits: self.cell_hits.load(Ordering::Relaxed),
+            cell_misses: self.cell_misses.load(Ordering::Relaxed),
+            chunk_hits: self.chunk_hits.load(Ordering::Relaxed),
+            chunk_misses: self.chunk_misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// drop every cached value and reset the stats
+    pub fn clear(&self) {
+        self.cells.lock().expect("cell cache lock").clear();
+        self.chunks.lock().expect("chunk cache lock").clear();
+        for counter in [
+            &self.cell_hits,
+            &self.cell_misses,
+            &self.chunk_hits,
+            &self.chunk_misses,
+            &self.evictions,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+const NONE: usize = usize::MAX;
+
+struct LruEntry<K, V> {
+    key: K,
+    value: V,
+    prev: usize,
+    next: usize,
+}
+
+// fixed capacity least recently used cache,
+// entries live in a vec and are linked from most (head) to least (tail) recently used
+struct LruCache<K, V> {
+    capacity: usize,
+    map: HashMap<K, usize, BuildHasherDefault<FxHasher>>,
+    entries: Vec<LruEntry<K, V>>,
+    head: usize,
+    tail: usize,
+}
+
+impl<K: Hash + Eq + Copy, V> LruCache<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            map: HashMap::default(),
+            entries: Vec::with_capacity(capacity),
+            head: NONE,
+            tail: NONE,
+        }
+    }
+
+    fn get(&mut self, key: &K) -> Option<&V> {
+        let idx = *self.map.get(key)?;
+        self.detach(idx);
+        self.attach_front(idx);
+        Some(&self.entries[idx].value)
+    }
+
+    // returns true if the least recently used entry was evicted
+    fn insert(&mut self, key: K, value: V) -> bool {
+        if self.capacity == 0 {
+            return false;
+        }
+        if let Some(&idx) = self.map.get(&key) {
+            self.entries[idx].value = value;
+            self.detach(idx);
+            self.attach_front(idx);
+            return false;
+        }
+        if self.entries.len() < self.capacity {
+            let idx = self.entries.len();
+            self.entries.push(LruEntry {
+                key,
+                value,
+                prev: NONE,
+                next: NONE,
+            });
+            self.map.insert(key, idx);
+            self.attach_front(idx);
+            return false;
+        }
+        // reuse the slot of the least recently used entry
+        let idx = self.tail;
+        self.detach(idx);
+        self.map.remove(&self.entries[idx].key);
+        self.entries[idx].key = key;
+        self.entries[idx].value = value;
+        self.map.insert(key, idx);
+        self.attach_front(idx);
+        true
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.entries.clear();
+        self.head = NONE;
+        self.tail = NONE;
+    }
+
+    fn detach(&mut self, idx: usize) {
+        let (prev, next) = (self.entries[idx].prev, self.entries[idx].next);
+        match prev {
+            NONE => self.head = next,
+            prev => self.entries[prev].next = next,
+        }
+        match next {
+            NONE => self.tail = prev,
+            next => self.entries[next].prev = prev,
+        }
+    }
+
+    fn attach_front(&mut self, idx: usize) {
+        self.entries[idx].prev = NONE;
+        self.entries[idx].next = self.head;
+        match self.head {
+            NONE => self.tail = idx,
+            head => self.entries[head].prev = idx,
+        }
+        self.head = idx;
+    }
+}
@@ -0,0 +1,83 @@
+use alloc::vec::Vec;
+
+use crate::biome_picker::{BiomePicker, BiomeVariants};
+use crate::hash::{CellRng, HashVersion};
+use crate::math::floor;
+use crate::region::Region;
+use crate::warp::unwarp_coords;
+use crate::worley::Worley;
+
+/// a worley cell, found through its feature point.
+/// spawn ONE special structure per cell (temple, village) at (x, z)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CellSite<BiomeT> {
+    pub cell: (i32, i32),
+    pub biome: BiomeT,
+    /// world position of the cell's feature point, warp included.
+    /// with strong warps no position may warp exactly onto the feature point,
+    /// then it's the closest one unwarp_coords() found
+    pub x: f64,
+    pub z: f64,
+    seed: u64,
+    hash: HashVersion,
+}
+
+impl<BiomeT> CellSite<BiomeT> {
+    /// deterministic rng for this cell, use it to vary the structure or nudge
+    /// its position onto valid terrain
+    pub fn rng(&self) -> CellRng {
+        self.hash.rng(self.seed, self.cell.0, self.cell.1)
+    }
+}
+
+// keep site rolls unrelated to the biome picking rolls
+const SITE_SALT: u64 = 9001;
+
+impl<BiomeT, Picker> Worley<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants + 'static + Default,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    /// every cell whose feature point lies inside region.
+    /// regions that don't overlap never return the same cell,
+    /// so chunked generation spawns each cell structure exactly once
+    pub fn cell_sites(&self, region: Region) -> Vec<CellSite<BiomeT>> {
+        // feature points can be warped in from outside the region
+        let margin = self.warp_settings.strength.abs() as f64 + 1.0;
+        let min_x = floor(region.min.0 / self.zoom - margin) as i32;
+        let min_z = floor(region.min.1 / self.zoom - margin) as i32;
+        let max_x = floor(region.max.0 / self.zoom + margin) as i32;
+        let max_z = floor(region.max.1 / self.zoom + margin) as i32;
+
+        let mut sites = Vec::new();
+        for cell_x in min_x..=max_x {
+            for cell_z in min_z..=max_z {
+                let (fx, fz) = self.algorithm.cell_point(self.seed, cell_x, cell_z);
+                let (x, z) = unwarp_coords(
+                    &self.warp_settings.noise,
+                    self.warp_settings.strength,
+                    fx,
+                    fz,
+                );
+                let (x, z) = (x * self.zoom, z * self.zoom);
+                if !region.contains(x, z) {
+                    continue;
+                }
+                sites.push(CellSite {
+                    cell: (cell_x, cell_z),
+                    biome: self.biome_picker.pick_biome_versioned(
+                        self.picker_seed(),
+                        cell_x,
+                        cell_z,
+                        self.algorithm.hash_version(),
+                    ),
+                    x,
+                    z,
+                    seed: self.seed.wrapping_add(SITE_SALT),
+                    hash: self.algorithm.hash_version(),
+                });
+            }
+        }
+        sites
+    }
+}
@@ -0,0 +1,460 @@
+//! on disk (.worley.ron) format of Worley
+//!
+//! files carry a `version` field, files without one are version 0 (the layout before versioning):
+//! - `biome_generator` instead of `biome_picker`, `UniformDistribution` instead of `Any`
+//! - `distance_fn` instead of `distance_fn_config`
+//! - warp noise flattened into `noise_seed`, `noise_frequency`, ... fields
+//!
+//! version 0 warped with a bare FastNoise (`noise: (..)`), or FastNoise::default() when the file
+//! has none. it needs the "fast-noise" feature just like the noise_* fields, without it loading
+//! fails with ConfigError::MissingFeature (unless the warp strength is 0).
+//! version 0 files without `kill_percent_threshold` load without one, like they used to
+//!
+//! version 1 adds:
+//! - `algorithm`, files without it load as AlgorithmVersion::V0, the output they were made with
+//! - the warp noise tagged with its kind: `noise: Simplex((..))`. Noise::Custom is never saved
+//! - `picker_seed`, older files pick biomes with `seed`
+//!
+//! older versions are migrated when loading, saving always writes CONFIG_VERSION.
+//! loaded configs are checked with Worley::validate(), `normalize_weights: true` scales
+//! Weighted odds to sum to 1.0 first
+use std::fmt;
+use std::path::Path;
+
+#[cfg(feature = "fast-noise")]
+use bracket_fast_noise::prelude::{FastNoise, FractalType, NoiseType};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::algorithm::AlgorithmVersion;
+use crate::biome_picker::{BiomePicker, BiomeVariants};
+use crate::distance_fn::DistanceFn;
+use crate::error::WorleyError;
+use crate::noise::Noise;
+use crate::warp::WarpSettings;
+use crate::worley::Worley;
+
+/// version written by Worley::to_ron() and serde
+pub const CONFIG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    // file was written by a newer worley_biomes
+    UnsupportedVersion {
+        found: u32,
+        supported: u32,
+    },
+    // old and new name of the same setting, can't tell which one is meant
+    Conflict {
+        old: &'static str,
+        new: &'static str,
+    },
+    // ron syntax, or a field with the wrong type
+    Parse(String),
+    // Worley::to_ron() failed
+    Serialize(String),
+    // loaded fine, but the settings can't be sampled
+    Invalid(WorleyError),
+    // the setting needs a disabled cargo feature of worley_biomes
+    MissingFeature {
+        feature: &'static str,
+        setting: &'static str,
+    },
+    // reading the file failed
+    Io {
+        path: String,
+        err: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "worley config version {found} is newer than the supported version {supported}, \
+                 update worley_biomes or save the file with an older version"
+            ),
+            ConfigError::Conflict { old, new } => write!(
+                f,
+                "`{old}` (old name) and `{new}` are both set, remove `{old}`"
+            ),
+            ConfigError::Parse(err) => write!(f, "failed to parse worley config: {err}"),
+            ConfigError::Serialize(err) => write!(f, "failed to serialize worley config: {err}"),
+            ConfigError::Invalid(err) => write!(f, "invalid worley config: {err}"),
+            ConfigError::MissingFeature { feature, setting } => write!(
+                f,
+                "`{setting}` needs the \"{feature}\" feature of worley_biomes"
+            ),
+            ConfigError::Io { path, err } => {
+                write!(f, "failed to read worley config {path:?}: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Invalid(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+// lets WorleyBuilder::from_ron(..)?.build()? use one error type
+impl From<WorleyError> for ConfigError {
+    fn from(err: WorleyError) -> Self {
+        ConfigError::Invalid(err)
+    }
+}
+
+impl<BiomeT, Picker> Worley<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants + Serialize,
+    Picker: BiomePicker<BiomeT> + Default + Serialize,
+{
+    /// pretty RON of the current config version
+    pub fn to_ron(&self) -> Result<String, ConfigError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| ConfigError::Serialize(err.to_string()))
+    }
+}
+
+impl<BiomeT, Picker> Worley<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants + for<'de> Deserialize<'de>,
+    Picker: BiomePicker<BiomeT> + Default + for<'de> Deserialize<'de>,
+{
+    /// load a .worley.ron of any known version
+    pub fn from_ron(ron: &str) -> Result<Self, ConfigError> {
+        WorleyRepr::parse(ron)?.into_worley_validated()
+    }
+
+    /// same as from_ron(), reading the file at (path)
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::from_ron(&read_config(path.as_ref())?)
+    }
+}
+
+pub(crate) fn read_config(path: &Path) -> Result<String, ConfigError> {
+    std::fs::read_to_string(path).map_err(|err| ConfigError::Io {
+        path: path.display().to_string(),
+        err: err.to_string(),
+    })
+}
+
+impl<BiomeT, Picker> Serialize for Worley<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants + Serialize,
+    Picker: BiomePicker<BiomeT> + Default + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(rename = "Worley")]
+        struct WorleyFile<'a, Picker> {
+            version: u32,
+            biome_picker: &'a Picker,
+            zoom: f64,
+            distance_fn_config: DistanceFn,
+            sharpness: f64,
+            k: usize,
+            seed: u64,
+            picker_seed: Option<u64>,
+            algorithm: AlgorithmVersion,
+            warp_settings: &'a WarpSettings,
+            kill_percent_threshold: Option<f64>,
+        }
+        WorleyFile {
+            version: CONFIG_VERSION,
+            biome_picker: &self.biome_picker,
+            zoom: self.zoom,
+            distance_fn_config: self.distance_fn_config,
+            sharpness: self.sharpness,
+            k: self.k,
+            seed: self.seed,
+            picker_seed: self.picker_seed,
+            algorithm: self.algorithm,
+            warp_settings: &self.warp_settings,
+            kill_percent_threshold: self.kill_percent_threshold,
+        }
+        .serialize(serializer)
+    }
+}
+
+// a field that is in the file, written without Some(..)
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// every layout Worley was saved in, missing fields fall back to Worley::default()
+#[derive(Deserialize)]
+#[serde(bound = "Picker: Deserialize<'de>")]
+pub(crate) struct WorleyRepr<Picker> {
+    #[serde(default)]
+    version: u32,
+    // scale Weighted odds to sum to 1.0 instead of failing validation, not saved
+    #[serde(default)]
+    normalize_weights: bool,
+    #[serde(default, deserialize_with = "present", alias = "biome_generator")]
+    biome_picker: Option<Picker>,
+    #[serde(default, deserialize_with = "present")]
+    zoom: Option<f64>,
+    #[serde(default, deserialize_with = "present")]
+    distance_fn_config: Option<DistanceFn>,
+    // version 0
+    #[serde(default, deserialize_with = "present")]
+    distance_fn: Option<DistanceFn>,
+    #[serde(default, deserialize_with = "present")]
+    sharpness: Option<f64>,
+    #[serde(default, deserialize_with = "present")]
+    k: Option<usize>,
+    #[serde(default, deserialize_with = "present")]
+    seed: Option<u64>,
+    #[serde(default, deserialize_with = "present")]
+    picker_seed: Option<Option<u64>>,
+    #[serde(default, deserialize_with = "present")]
+    algorithm: Option<AlgorithmVersion>,
+    // converted in into_worley(), so its errors stay ConfigErrors instead of ron messages
+    #[serde(default, deserialize_with = "present")]
+    warp_settings: Option<WarpSettingsRepr>,
+    #[serde(default, deserialize_with = "present")]
+    kill_percent_threshold: Option<Option<f64>>,
+}
+
+impl<Picker: for<'de> Deserialize<'de>> WorleyRepr<Picker> {
+    pub(crate) fn parse(ron: &str) -> Result<Self, ConfigError> {
+        ron::from_str(ron).map_err(|err| match err.code {
+            // our own errors from nested settings, ron's position prefix makes them hard to read
+            ron::Error::Message(message) => ConfigError::Parse(format!(
+                "{message} (line {}, column {})",
+                err.span.start.line, err.span.start.col
+            )),
+            _ => ConfigError::Parse(err.to_string()),
+        })
+    }
+}
+
+impl<Picker> WorleyRepr<Picker> {
+    /// migrate to the current Worley, without validating it
+    pub(crate) fn into_worley<BiomeT>(self) -> Result<Worley<BiomeT, Picker>, ConfigError>
+    where
+        BiomeT: BiomeVariants,
+        Picker: BiomePicker<BiomeT> + Default,
+    {
+        if self.version > CONFIG_VERSION {
+            return Err(ConfigError::UnsupportedVersion {
+                found: self.version,
+                supported: CONFIG_VERSION,
+            });
+        }
+        let distance_fn_config = match (self.distance_fn, self.distance_fn_config) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::Conflict {
+                    old: "distance_fn",
+                    new: "distance_fn_config",
+                });
+            }
+            (old, new) => new.or(old),
+        };
+
+        let mut worley = Worley::default();
+        if let Some(distance_fn_config) = distance_fn_config {
+            worley.distance_fn = distance_fn_config.to_func();
+            worley.distance_fn_config = distance_fn_config;
+        }
+        if let Some(biome_picker) = self.biome_picker {
+            worley.biome_picker = biome_picker;
+        }
+        if self.normalize_weights {
+            worley.biome_picker.normalize();
+        }
+        if let Some(warp_settings) = self.warp_settings {
+            let version_0_noise = (self.version == 0 && !warp_settings.has_noise())
+                .then(|| warp_settings.version_0_noise());
+            worley.warp_settings = warp_settings.try_into()?;
+            if let Some(noise) = version_0_noise {
+                worley.warp_settings.noise = noise?;
+            }
+        }
+        worley.zoom = self.zoom.unwrap_or(worley.zoom);
+        worley.sharpness = self.sharpness.unwrap_or(worley.sharpness);
+        worley.k = self.k.unwrap_or(worley.k);
+        worley.seed = self.seed.unwrap_or(worley.seed);
+        worley.picker_seed = self.picker_seed.unwrap_or(worley.picker_seed);
+        // files from before versioned algorithms were made with V0
+        worley.algorithm = self.algorithm.unwrap_or(AlgorithmVersion::V0);
+        worley.kill_percent_threshold = match self.kill_percent_threshold {
+            Some(threshold) => threshold,
+            // a missing Option was None before versioning
+            None if self.version == 0 => None,
+            None => worley.kill_percent_threshold,
+        };
+        Ok(worley)
+    }
+
+    pub(crate) fn into_worley_validated<BiomeT>(self) -> Result<Worley<BiomeT, Picker>, ConfigError>
+    where
+        BiomeT: BiomeVariants,
+        Picker: BiomePicker<BiomeT> + Default,
+    {
+        let worley = self.into_worley()?;
+        worley.validate()?;
+        Ok(worley)
+    }
+}
+
+impl<BiomeT, Picker> TryFrom<WorleyRepr<Picker>> for Worley<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    type Error = ConfigError;
+
+    fn try_from(repr: WorleyRepr<Picker>) -> Result<Self, Self::Error> {
+        repr.into_worley_validated()
+    }
+}
+
+/// WarpSettings, either with a nested (noise) or the version 0 flattened noise_* fields
+#[derive(Deserialize)]
+pub(crate) struct WarpSettingsRepr {
+    #[serde(default)]
+    strength: f32,
+    #[serde(default, deserialize_with = "present")]
+    noise: Option<NoiseRepr>,
+    // version 0
+    #[serde(default, deserialize_with = "present")]
+    noise_seed: Option<u64>,
+    #[serde(default, deserialize_with = "present")]
+    noise_frequency: Option<f32>,
+    #[serde(default, deserialize_with = "present")]
+    noise_fractal_lacunarity: Option<f32>,
+    #[serde(default, deserialize_with = "present")]
+    noise_fractal_gain: Option<f32>,
+    #[serde(default, deserialize_with = "present")]
+    noise_fractal_octaves: Option<i32>,
+    #[serde(default, deserialize_with = "present")]
+    noise_noise_type: Option<LegacyNoiseType>,
+    #[serde(default, deserialize_with = "present")]
+    noise_fractal_type: Option<LegacyFractalType>,
+}
+
+// version 0 noise is always FastNoise, without the feature it can only be detected
+#[cfg(feature = "fast-noise")]
+type LegacyNoiseType = NoiseType;
+#[cfg(feature = "fast-noise")]
+type LegacyFractalType = FractalType;
+#[cfg(not(feature = "fast-noise"))]
+type LegacyNoiseType = serde::de::IgnoredAny;
+#[cfg(not(feature = "fast-noise"))]
+type LegacyFractalType = serde::de::IgnoredAny;
+
+// version 1 tags the noise with its kind (`Simplex((..))`),
+// version 0 wrote a bare FastNoise
+#[derive(Deserialize)]
+#[serde(
+    untagged,
+    expecting = "expected warp noise Value((..)), Simplex((..)) or FastNoise((..))"
+)]
+enum NoiseRepr {
+    Noise(Noise),
+    #[cfg(feature = "fast-noise")]
+    FastNoise(FastNoise),
+}
+
+impl WarpSettingsRepr {
+    fn has_legacy_noise(&self) -> bool {
+        self.noise_seed.is_some()
+            || self.noise_frequency.is_some()
+            || self.noise_fractal_lacunarity.is_some()
+            || self.noise_fractal_gain.is_some()
+            || self.noise_fractal_octaves.is_some()
+            || self.noise_noise_type.is_some()
+            || self.noise_fractal_type.is_some()
+    }
+
+    fn has_noise(&self) -> bool {
+        self.noise.is_some() || self.has_legacy_noise()
+    }
+
+    // version 0 files without noise warped with FastNoise::default()
+    #[cfg(feature = "fast-noise")]
+    fn version_0_noise(&self) -> Result<Noise, ConfigError> {
+        Ok(Noise::FastNoise(FastNoise::default()))
+    }
+
+    // without warp the noise doesn't matter
+    #[cfg(not(feature = "fast-noise"))]
+    fn version_0_noise(&self) -> Result<Noise, ConfigError> {
+        if self.strength == 0.0 {
+            return Ok(Noise::default());
+        }
+        Err(ConfigError::MissingFeature {
+            feature: "fast-noise",
+            setting: "warp_settings.noise",
+        })
+    }
+
+    #[cfg(feature = "fast-noise")]
+    fn legacy_noise(&self) -> Result<Noise, ConfigError> {
+        let mut noise = FastNoise::seeded(self.noise_seed.unwrap_or_default());
+        if let Some(frequency) = self.noise_frequency {
+            noise.set_frequency(frequency);
+        }
+        if let Some(lacunarity) = self.noise_fractal_lacunarity {
+            noise.set_fractal_lacunarity(lacunarity);
+        }
+        if let Some(gain) = self.noise_fractal_gain {
+            noise.set_fractal_gain(gain);
+        }
+        if let Some(octaves) = self.noise_fractal_octaves {
+            noise.set_fractal_octaves(octaves);
+        }
+        if let Some(noise_type) = self.noise_noise_type {
+            noise.set_noise_type(noise_type);
+        }
+        if let Some(fractal_type) = self.noise_fractal_type {
+            noise.set_fractal_type(fractal_type);
+        }
+        Ok(Noise::FastNoise(noise))
+    }
+
+    #[cfg(not(feature = "fast-noise"))]
+    fn legacy_noise(&self) -> Result<Noise, ConfigError> {
+        Err(ConfigError::MissingFeature {
+            feature: "fast-noise",
+            setting: "warp_settings.noise_*",
+        })
+    }
+}
+
+impl TryFrom<WarpSettingsRepr> for WarpSettings {
+    type Error = ConfigError;
+
+    fn try_from(repr: WarpSettingsRepr) -> Result<Self, Self::Error> {
+        let legacy = repr.has_legacy_noise().then(|| repr.legacy_noise());
+        let noise = match (repr.noise, legacy) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::Conflict {
+                    old: "warp_settings.noise_*",
+                    new: "warp_settings.noise",
+                });
+            }
+            (None, Some(legacy)) => legacy?,
+            (Some(NoiseRepr::Noise(noise)), None) => noise,
+            #[cfg(feature = "fast-noise")]
+            (Some(NoiseRepr::FastNoise(noise)), None) => {
+                Noise::FastNoise(crate::noise::reseed_fast_noise(noise))
+            }
+            (None, None) => Noise::default(),
+        };
+        Ok(WarpSettings {
+            strength: repr.strength,
+            noise,
+        })
+    }
+}
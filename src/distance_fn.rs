@@ -1,9 +1,14 @@
+#[cfg(feature = "bevy")]
+use bevy::reflect::Reflect;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use crate::math::sqrt;
+
 ///! what distance function to use to measure distance to worlay
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum DistanceFn {
     Euclidean,
@@ -17,11 +22,11 @@ pub enum DistanceFn {
 impl DistanceFn {
     pub fn to_func(&self) -> fn(f64, f64) -> f64 {
         match self {
-            DistanceFn::Euclidean => |dx, dz| (dx * dx + dz * dz).sqrt(),
+            DistanceFn::Euclidean => |dx, dz| sqrt(dx * dx + dz * dz),
             DistanceFn::EuclideanSquared => |dx, dz| dx * dx + dz * dz,
             DistanceFn::Manhattan => |dx, dz| dx.abs() + dz.abs(),
             DistanceFn::Chebyshev => |dx, dz| dx.abs().max(dz.abs()),
-            DistanceFn::Hybrid => |dx, dz| ((dx * dx + dz * dz).sqrt() + dx.abs() + dz.abs()) / 2.0,
+            DistanceFn::Hybrid => |dx, dz| (sqrt(dx * dx + dz * dz) + dx.abs() + dz.abs()) / 2.0,
         }
     }
 }
@@ -0,0 +1,54 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::hash::HashVersion;
+use crate::math::floor;
+use crate::utils::unit_f64;
+
+/// pattern used to roll which biome a block belongs to, when picking ONE biome by weight
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum DitherPattern {
+    // random per block, natural noisy borders
+    #[default]
+    Hash,
+    // 8x8 bayer matrix, regular cross hatched borders
+    Ordered,
+    // interleaved gradient noise, evenly spread borders without clumps
+    BlueNoise,
+}
+
+#[rustfmt::skip]
+const BAYER_8X8: [u8; 64] = [
+     0, 32,  8, 40,  2, 34, 10, 42,
+    48, 16, 56, 24, 50, 18, 58, 26,
+    12, 44,  4, 36, 14, 46,  6, 38,
+    60, 28, 52, 20, 62, 30, 54, 22,
+     3, 35, 11, 43,  1, 33,  9, 41,
+    51, 19, 59, 27, 49, 17, 57, 25,
+    15, 47,  7, 39, 13, 45,  5, 37,
+    63, 31, 55, 23, 61, 29, 53, 21,
+];
+
+impl DitherPattern {
+    /// deterministic roll in 0.0..1.0 for a block position
+    pub fn roll(&self, hash: HashVersion, seed: u64, x: i32, z: i32) -> f64 {
+        match self {
+            DitherPattern::Hash => unit_f64(hash.hash(seed, x, z)),
+            DitherPattern::Ordered => {
+                let idx = (z.rem_euclid(8) * 8 + x.rem_euclid(8)) as usize;
+                (BAYER_8X8[idx] as f64 + 0.5) / 64.0
+            }
+            DitherPattern::BlueNoise => {
+                // offset the pattern per seed, so seeds don't share the same borders
+                let offset = hash.hash(seed, 0, 0);
+                let x = x.wrapping_add((offset & 0xFFFF) as i32) as f64;
+                let z = z.wrapping_add(((offset >> 16) & 0xFFFF) as i32) as f64;
+                // Jorge Jimenez, interleaved gradient noise
+                let f = 0.06711056 * x + 0.00583715 * z;
+                let f = 52.9829189 * (f - floor(f));
+                f - floor(f)
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use core::fmt;
+
+use crate::noise::NoiseKind;
+
+/// a Worley config that would panic or produce garbage when sampled, see Worley::validate()
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorleyError {
+    // SimpleBiomePicker::Any with BiomeVariants::variants() returning nothing
+    NoVariants,
+    // SimpleBiomePicker::AnyOf with an empty list
+    EmptyAnyOf,
+    // SimpleBiomePicker::Weighted with an empty list
+    EmptyWeights,
+    // negative, NaN or infinite weight
+    InvalidWeight {
+        index: usize,
+        weight: f32,
+    },
+    // weights have to add up to 1.0
+    WeightSum(f32),
+    // k has to be 1 -> MAX_K
+    InvalidK(usize),
+    // zoom has to be finite and above 0
+    InvalidZoom(f64),
+    // sharpness has to be finite
+    InvalidSharpness(f64),
+    // kill_percent_threshold has to be 0.0 -> below 1.0
+    InvalidKillThreshold(f64),
+    // warp strength has to be finite
+    InvalidWarpStrength(f32),
+    // ScatterPattern::JitteredGrid spacing has to be finite and above 0
+    InvalidScatterSpacing(f64),
+    // ScatterPattern::JitteredGrid jitter has to be 0.0 -> 1.0
+    InvalidScatterJitter(f64),
+    // ScatterPattern::PoissonDisk radius has to be finite and above 0
+    InvalidScatterRadius(f64),
+    // ChunkSettings chunk_size has to be finite and above 0
+    InvalidChunkSize(f64),
+    // ChunkSettings needs 0 <= load_distance <= unload_distance
+    InvalidChunkDistance {
+        load: i32,
+        unload: i32,
+    },
+    // a WorleyBuilder setting the warp noise doesn't have
+    UnsupportedNoiseSetting {
+        setting: &'static str,
+        kind: NoiseKind,
+    },
+}
+
+impl fmt::Display for WorleyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorleyError::NoVariants => write!(
+                f,
+                "biome picker Any has no biomes to pick from, BiomeVariants::variants() is empty"
+            ),
+            WorleyError::EmptyAnyOf => write!(f, "biome picker AnyOf needs at least one biome"),
+            WorleyError::EmptyWeights => {
+                write!(f, "biome picker Weighted needs at least one biome")
+            }
+            WorleyError::InvalidWeight { index, weight } => write!(
+                f,
+                "biome picker Weighted has invalid weight {weight} at index {index}, \
+                 weights must be finite and not negative"
+            ),
+            WorleyError::WeightSum(sum) => write!(
+                f,
+                "biome picker Weighted weights sum to {sum}, expected 1.0, \
+                 fix the weights or normalize them"
+            ),
+            WorleyError::InvalidK(k) => {
+                write!(f, "k is {k}, expected 1 -> {}", crate::worley::MAX_K)
+            }
+            WorleyError::InvalidZoom(zoom) => {
+                write!(f, "zoom is {zoom}, expected a finite value above 0")
+            }
+            WorleyError::InvalidSharpness(sharpness) => {
+                write!(f, "sharpness is {sharpness}, expected a finite value")
+            }
+            WorleyError::InvalidKillThreshold(threshold) => write!(
+                f,
+                "kill_percent_threshold is {threshold}, expected 0.0 -> below 1.0"
+            ),
+            WorleyError::InvalidWarpStrength(strength) => {
+                write!(f, "warp strength is {strength}, expected a finite value")
+            }
+            WorleyError::InvalidScatterSpacing(spacing) => {
+                write!(
+                    f,
+                    "scatter spacing is {spacing}, expected a finite value above 0"
+                )
+            }
+            WorleyError::InvalidScatterJitter(jitter) => {
+                write!(f, "scatter jitter is {jitter}, expected 0.0 -> 1.0")
+            }
+            WorleyError::InvalidScatterRadius(radius) => {
+                write!(
+                    f,
+                    "scatter radius is {radius}, expected a finite value above 0"
+                )
+            }
+            WorleyError::InvalidChunkSize(size) => {
+                write!(f, "chunk size is {size}, expected a finite value above 0")
+            }
+            WorleyError::InvalidChunkDistance { load, unload } => write!(
+                f,
+                "chunk load distance is {load} and unload distance {unload}, \
+                 expected 0 <= load distance <= unload distance"
+            ),
+            WorleyError::UnsupportedNoiseSetting { setting, kind } => write!(
+                f,
+                "`{setting}` only applies to FastNoise, the warp noise is {kind:?}, \
+                 set warp_noise(FastNoise::default()) first"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for WorleyError {}
@@ -0,0 +1,341 @@
+//! C API, for tools that have to reproduce the biomes of a rust world (editor plugins, other engines).
+//! the header is include/worley_biomes.h, regenerate it after changing this file:
+//! `cbindgen --config cbindgen.toml --output include/worley_biomes.h`
+//!
+//! biomes are integer ids: the index of their name in the biome list given to
+//! wb_worley_from_ron(). pass the names in the order of the rust enum's
+//! BiomeVariants::variants() and the ids match the enum, so `Any` picks the same biomes.
+//! the biome list is process wide (BiomeRegistry::install()), every worley created
+//! through the C API has to use the same list
+use std::ffi::{CStr, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+
+use crate::biome_picker::SimpleBiomePicker;
+use crate::blend::BiomeBlend;
+use crate::region::Region;
+use crate::registry::{BiomeId, BiomeRegistry};
+use crate::worley::{MAX_K, Worley};
+
+/// most weights a sample can have
+pub const WB_MAX_K: usize = 9;
+/// written for a missing biome: dominant of an empty sample, unused region slots
+pub const WB_NO_BIOME: u16 = u16::MAX;
+
+const _: () = assert!(WB_MAX_K == MAX_K);
+
+/// a Worley with a SimpleBiomePicker, created by wb_worley_from_ron()
+pub struct WbWorley {
+    worley: Worley<BiomeId, SimpleBiomePicker<BiomeId>>,
+    registry: &'static BiomeRegistry,
+}
+
+/// one biome of a sample
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WbBiomeWeight {
+    pub weight: f64,
+    pub biome: u16,
+}
+
+const EMPTY_WEIGHT: WbBiomeWeight = WbBiomeWeight {
+    weight: 0.0,
+    biome: WB_NO_BIOME,
+};
+
+/// create a worley from a .worley.ron config (any version worley_biomes can load).
+/// returns NULL on failure, with a message written to (error) when it isn't NULL.
+///
+/// # Safety
+/// (biome_names) points to (biome_count) NUL terminated strings, (config_ron) is
+/// NUL terminated, (error) is NULL or writable for (error_len) bytes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wb_worley_from_ron(
+    biome_names: *const *const c_char,
+    biome_count: usize,
+    config_ron: *const c_char,
+    error: *mut c_char,
+    error_len: usize,
+) -> *mut WbWorley {
+    let result = catch_unwind(AssertUnwindSafe(|| -> Result<_, String> {
+        // SAFETY: guaranteed by the caller
+        let (names, ron) = unsafe { read_inputs(biome_names, biome_count, config_ron)? };
+        let registry = install_registry(&names)?;
+        let worley = Worley::from_ron(ron).map_err(|err| err.to_string())?;
+        Ok(Box::new(WbWorley { worley, registry }))
+    }));
+    match result {
+        Ok(Ok(worley)) => Box::into_raw(worley),
+        Ok(Err(message)) => {
+            // SAFETY: guaranteed by the caller
+            unsafe { write_error(error, error_len, &message) };
+            ptr::null_mut()
+        }
+        Err(_) => {
+            // SAFETY: guaranteed by the caller
+            unsafe { write_error(error, error_len, "worley_biomes panicked") };
+            ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+/// (worley) is NULL or from wb_worley_from_ron(), and not used after this
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wb_worley_free(worley: *mut WbWorley) {
+    if !worley.is_null() {
+        // SAFETY: created by Box::into_raw in wb_worley_from_ron
+        drop(unsafe { Box::from_raw(worley) });
+    }
+}
+
+/// weights at (x, z) in the order of Worley::get(), returns how many were written
+/// (at most WB_MAX_K and out_len).
+///
+/// # Safety
+/// (worley) is valid, (out) is writable for (out_len) weights
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wb_worley_sample(
+    worley: *const WbWorley,
+    x: f64,
+    z: f64,
+    out: *mut WbBiomeWeight,
+    out_len: usize,
+) -> usize {
+    // SAFETY: guaranteed by the caller
+    let (Some(worley), Some(out)) = (unsafe { (worley.as_ref(), out_slice(out, out_len)) }) else {
+        return 0;
+    };
+    let weights = worley.worley.get(x, z);
+    let written = weights.len().min(out.len());
+    for (slot, (weight, biome)) in out.iter_mut().zip(weights.iter()) {
+        *slot = WbBiomeWeight {
+            weight: *weight,
+            biome: biome.0,
+        };
+    }
+    written
+}
+
+/// strongest biome at (x, z), WB_NO_BIOME if there is none
+///
+/// # Safety
+/// (worley) is valid
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wb_worley_dominant(worley: *const WbWorley, x: f64, z: f64) -> u16 {
+    // SAFETY: guaranteed by the caller
+    match unsafe { worley.as_ref() } {
+        Some(worley) => biome_or_none(worley.worley.get(x, z).dominant()),
+        None => WB_NO_BIOME,
+    }
+}
+
+/// one biome per block, see Worley::pick_dithered(). WB_NO_BIOME if there is none
+///
+/// # Safety
+/// (worley) is valid
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wb_worley_pick_dithered(worley: *const WbWorley, x: f64, z: f64) -> u16 {
+    // SAFETY: guaranteed by the caller
+    match unsafe { worley.as_ref() } {
+        Some(worley) => biome_or_none(worley.worley.pick_dithered(x, z)),
+        None => WB_NO_BIOME,
+    }
+}
+
+/// sample a (width) * (depth) grid covering min -> max (max exclusive), see Worley::sample_region().
+/// sample (x, z) is written to out[(z * width + x) * stride ..], (stride) weights per sample,
+/// unused slots get weight 0.0 and WB_NO_BIOME. a (stride) below the worley's k keeps only the
+/// first (stride) weights of Worley::get(): the strongest with AlgorithmVersion::V1, any with V0.
+/// returns 0, or -1 when (stride) is 0 or (out_len) is smaller than width * depth * stride
+///
+/// # Safety
+/// (worley) is valid, (out) is writable for (out_len) weights
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn wb_worley_sample_region(
+    worley: *const WbWorley,
+    min_x: f64,
+    min_z: f64,
+    max_x: f64,
+    max_z: f64,
+    width: usize,
+    depth: usize,
+    stride: usize,
+    out: *mut WbBiomeWeight,
+    out_len: usize,
+) -> i32 {
+    if stride == 0 {
+        return -1;
+    }
+    let Some(needed) = width.checked_mul(depth).and_then(|n| n.checked_mul(stride)) else {
+        return -1;
+    };
+    // SAFETY: guaranteed by the caller
+    let (Some(worley), Some(out)) = (unsafe { (worley.as_ref(), out_slice(out, out_len)) }) else {
+        return -1;
+    };
+    if out.len() < needed {
+        return -1;
+    }
+    let grid =
+        worley
+            .worley
+            .sample_region(Region::new((min_x, min_z), (max_x, max_z)), width, depth);
+    for (sample, slots) in grid.samples.iter().zip(out.chunks_exact_mut(stride)) {
+        slots.fill(EMPTY_WEIGHT);
+        for (slot, (weight, biome)) in slots.iter_mut().zip(sample.iter()) {
+            *slot = WbBiomeWeight {
+                weight: *weight,
+                biome: biome.0,
+            };
+        }
+    }
+    0
+}
+
+/// strongest biome of every sample of a (width) * (depth) grid, row major,
+/// same grid as wb_worley_sample_region(). returns 0, or -1 when (out_len) < width * depth
+///
+/// # Safety
+/// (worley) is valid, (out) is writable for (out_len) ids
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn wb_worley_dominant_region(
+    worley: *const WbWorley,
+    min_x: f64,
+    min_z: f64,
+    max_x: f64,
+    max_z: f64,
+    width: usize,
+    depth: usize,
+    out: *mut u16,
+    out_len: usize,
+) -> i32 {
+    let Some(needed) = width.checked_mul(depth) else {
+        return -1;
+    };
+    // SAFETY: guaranteed by the caller
+    let (Some(worley), Some(out)) = (unsafe { (worley.as_ref(), out_slice(out, out_len)) }) else {
+        return -1;
+    };
+    if out.len() < needed {
+        return -1;
+    }
+    let grid =
+        worley
+            .worley
+            .sample_region(Region::new((min_x, min_z), (max_x, max_z)), width, depth);
+    for (slot, sample) in out.iter_mut().zip(grid.samples.iter()) {
+        *slot = biome_or_none(sample.dominant());
+    }
+    0
+}
+
+/// how many biomes the worley knows
+///
+/// # Safety
+/// (worley) is valid
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wb_biome_count(worley: *const WbWorley) -> usize {
+    // SAFETY: guaranteed by the caller
+    unsafe { worley.as_ref() }.map_or(0, |worley| worley.registry.len())
+}
+
+/// id of a biome name, WB_NO_BIOME if it isn't known
+///
+/// # Safety
+/// (worley) is valid, (name) is NUL terminated
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wb_biome_id(worley: *const WbWorley, name: *const c_char) -> u16 {
+    // SAFETY: guaranteed by the caller
+    let (Some(worley), Some(name)) = (unsafe { worley.as_ref() }, unsafe { read_str(name) }) else {
+        return WB_NO_BIOME;
+    };
+    biome_or_none(worley.registry.id(name))
+}
+
+/// writes the NUL terminated name of (biome) to (out), truncated to (out_len) bytes.
+/// returns the length of the full name like snprintf, 0 for an unknown biome
+///
+/// # Safety
+/// (worley) is valid, (out) is NULL or writable for (out_len) bytes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wb_biome_name(
+    worley: *const WbWorley,
+    biome: u16,
+    out: *mut c_char,
+    out_len: usize,
+) -> usize {
+    // SAFETY: guaranteed by the caller
+    let Some(name) = unsafe { worley.as_ref() }.and_then(|w| w.registry.name(BiomeId(biome)))
+    else {
+        return 0;
+    };
+    // SAFETY: guaranteed by the caller
+    unsafe { write_error(out, out_len, name) };
+    name.len()
+}
+
+fn biome_or_none(biome: Option<BiomeId>) -> u16 {
+    biome.map_or(WB_NO_BIOME, |biome| biome.0)
+}
+
+// the C API shares one registry, see BiomeRegistry::install_names()
+fn install_registry(names: &[&str]) -> Result<&'static BiomeRegistry, String> {
+    // WB_NO_BIOME can't be a biome
+    if names.len() > WB_NO_BIOME as usize {
+        return Err(format!("at most {WB_NO_BIOME} biomes"));
+    }
+    BiomeRegistry::install_names(names).map_err(|err| err.to_string())
+}
+
+unsafe fn read_inputs<'a>(
+    biome_names: *const *const c_char,
+    biome_count: usize,
+    config_ron: *const c_char,
+) -> Result<(Vec<&'a str>, &'a str), String> {
+    if biome_names.is_null() && biome_count > 0 {
+        return Err("biome_names is NULL".to_string());
+    }
+    let mut names = Vec::with_capacity(biome_count);
+    for i in 0..biome_count {
+        // SAFETY: (biome_names) has (biome_count) entries
+        let name = unsafe { read_str(*biome_names.add(i)) }
+            .ok_or_else(|| format!("biome name {i} is NULL or not UTF-8"))?;
+        names.push(name);
+    }
+    // SAFETY: guaranteed by the caller
+    let ron =
+        unsafe { read_str(config_ron) }.ok_or_else(|| "config is NULL or not UTF-8".to_string())?;
+    Ok((names, ron))
+}
+
+unsafe fn read_str<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        return None;
+    }
+    // SAFETY: NUL terminated, guaranteed by the caller
+    unsafe { CStr::from_ptr(s) }.to_str().ok()
+}
+
+unsafe fn out_slice<'a, T>(out: *mut T, len: usize) -> Option<&'a mut [T]> {
+    if out.is_null() {
+        return None;
+    }
+    // SAFETY: writable for (len) elements, guaranteed by the caller
+    Some(unsafe { std::slice::from_raw_parts_mut(out, len) })
+}
+
+// NUL terminated, truncated to (len) bytes
+unsafe fn write_error(out: *mut c_char, len: usize, message: &str) {
+    if out.is_null() || len == 0 {
+        return;
+    }
+    let n = message.len().min(len - 1);
+    // SAFETY: (out) is writable for (len) bytes, n < len
+    unsafe {
+        ptr::copy_nonoverlapping(message.as_ptr() as *const c_char, out, n);
+        *out.add(n) = 0;
+    }
+}
@@ -0,0 +1,137 @@
+use alloc::boxed::Box;
+
+use rand::{RngCore, rngs::StdRng};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{hash_u64, seeded_rng};
+
+/// how cells are hashed and rngs seeded, part of what a seed generates.
+/// everyone generating the same world (client + server) must use the same version
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum HashVersion {
+    // FxHasher + StdRng, the original output.
+    // differs between 32 and 64 bit targets (wasm32), and may change with rand updates
+    #[default]
+    V0,
+    // portable_hash() + SplitMix64, same output on every target and version
+    V1,
+}
+
+impl HashVersion {
+    /// hash of a seed and cell/block position
+    #[inline(always)]
+    pub fn hash(&self, seed: u64, x: i32, z: i32) -> u64 {
+        match self {
+            HashVersion::V0 => hash_u64(seed, x, z),
+            HashVersion::V1 => portable_hash(seed, x, z),
+        }
+    }
+
+    /// rng seeded from a seed and cell/block position
+    pub fn rng(&self, seed: u64, x: i32, z: i32) -> CellRng {
+        match self {
+            HashVersion::V0 => CellRng(RngImpl::Std(Box::new(seeded_rng(seed, x, z)))),
+            HashVersion::V1 => CellRng(RngImpl::SplitMix(SplitMix64::new(portable_hash(
+                seed, x, z,
+            )))),
+        }
+    }
+}
+
+/// SplitMix64 finalizer (Steele, Lea, Flood), mixes every input bit into every output bit
+#[inline(always)]
+pub fn mix64(mut h: u64) -> u64 {
+    h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^ (h >> 31)
+}
+
+/// platform independent hash of a seed and position, used by HashVersion::V1:
+/// mix64(mix64(seed ^ 0x9E3779B97F4A7C15) ^ (x as u32) << 32 | (z as u32))
+#[inline(always)]
+pub fn portable_hash(seed: u64, x: i32, z: i32) -> u64 {
+    let position = ((x as u32 as u64) << 32) | (z as u32 as u64);
+    mix64(mix64(seed ^ GOLDEN_GAMMA) ^ position)
+}
+
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// platform independent hash of bytes (strings), 8 bytes little endian at a time,
+/// the last chunk zero padded and the length mixed in first
+pub fn portable_hash_bytes(bytes: &[u8]) -> u64 {
+    let mut h = mix64(bytes.len() as u64 ^ GOLDEN_GAMMA);
+    for chunk in bytes.chunks(8) {
+        let mut word = [0u8; 8];
+        word[..chunk.len()].copy_from_slice(chunk);
+        h = mix64(h.wrapping_add(GOLDEN_GAMMA) ^ u64::from_le_bytes(word));
+    }
+    h
+}
+
+/// SplitMix64 (Steele, Lea, Flood), output only depends on the seed
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl RngCore for SplitMix64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        mix64(self.state)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+/// rng from HashVersion::rng().
+/// for V1 the raw next_u32/next_u64 output is portable, values drawn through rand's
+/// distributions (random(), random_range()) are as stable as rand makes them
+#[derive(Debug, Clone)]
+pub struct CellRng(RngImpl);
+
+#[derive(Debug, Clone)]
+enum RngImpl {
+    Std(Box<StdRng>),
+    SplitMix(SplitMix64),
+}
+
+impl RngCore for CellRng {
+    fn next_u32(&mut self) -> u32 {
+        match &mut self.0 {
+            RngImpl::Std(rng) => rng.next_u32(),
+            RngImpl::SplitMix(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match &mut self.0 {
+            RngImpl::Std(rng) => rng.next_u64(),
+            RngImpl::SplitMix(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match &mut self.0 {
+            RngImpl::Std(rng) => rng.fill_bytes(dest),
+            RngImpl::SplitMix(rng) => rng.fill_bytes(dest),
+        }
+    }
+}
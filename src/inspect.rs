@@ -0,0 +1,120 @@
+use alloc::vec::Vec;
+
+use tinyvec::TinyVec;
+
+use crate::biome_picker::{BiomePicker, BiomeVariants};
+use crate::worley::{MAX_K, SampleObserver, Worley};
+
+/// why a candidate cell did or didn't end up in Worley::get()
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CandidateFate {
+    /// one of the returned biomes
+    Kept,
+    /// not among the k closest
+    BeyondK,
+    /// among the k closest, but its weight was below kill_percent_threshold
+    Killed,
+}
+
+/// one of the neighboring cells Worley::get() looks at
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Candidate<BiomeT> {
+    pub cell: (i32, i32),
+    pub biome: BiomeT,
+    /// feature point in cell space
+    pub feature_point: (f64, f64),
+    /// raw distance_fn output, in cell space
+    pub distance: f64,
+    /// share of the k closest before the kill threshold, None when beyond k
+    pub weight: Option<f64>,
+    pub fate: CandidateFate,
+}
+
+/// every step of Worley::get() at one position, for debugging sharpness, k and the kill threshold
+#[derive(Debug, Clone)]
+pub struct SampleInspection<BiomeT: Default> {
+    /// world position that was sampled
+    pub world: (f64, f64),
+    /// position after zoom and warp, in cell space
+    pub position: (f64, f64),
+    /// cell that contains position
+    pub cell: (i32, i32),
+    /// all neighboring cells, closest first
+    pub candidates: Vec<Candidate<BiomeT>>,
+    /// the same weights Worley::get() returns
+    pub weights: TinyVec<[(f64, BiomeT); 3]>,
+}
+
+impl<BiomeT, Picker> Worley<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants + 'static + Default,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    /// Worley::get() at (x, z), keeping the candidates it dropped along the way.
+    /// slower than get(), meant for debug tools
+    pub fn inspect(&self, x: f64, z: f64) -> SampleInspection<BiomeT> {
+        let mut recorder = Recorder {
+            position: (0.0, 0.0),
+            cell: (0, 0),
+            candidates: Vec::with_capacity(MAX_K),
+        };
+        let weights = self.get_with_cells(
+            x,
+            z,
+            |cell_x, cell_z| self.cell(cell_x, cell_z),
+            &mut recorder,
+        );
+        let mut candidates = recorder.candidates;
+        candidates.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        SampleInspection {
+            world: (x, z),
+            position: recorder.position,
+            cell: recorder.cell,
+            candidates,
+            weights,
+        }
+    }
+}
+
+// collects the steps of get_with_cells() for inspect()
+struct Recorder<BiomeT> {
+    position: (f64, f64),
+    cell: (i32, i32),
+    // in NEIGHBOR_OFFSETS order until inspect() sorts them
+    candidates: Vec<Candidate<BiomeT>>,
+}
+
+impl<BiomeT> SampleObserver<BiomeT> for Recorder<BiomeT> {
+    fn position(&mut self, position: (f64, f64), cell: (i32, i32)) {
+        self.position = position;
+        self.cell = cell;
+    }
+
+    fn candidate(
+        &mut self,
+        _index: usize,
+        cell: (i32, i32),
+        feature_point: (f64, f64),
+        distance: f64,
+        biome: BiomeT,
+    ) {
+        self.candidates.push(Candidate {
+            cell,
+            biome,
+            feature_point,
+            distance,
+            weight: None,
+            fate: CandidateFate::BeyondK,
+        });
+    }
+
+    fn share(&mut self, index: usize, weight: f64, kept: bool) {
+        let candidate = &mut self.candidates[index];
+        candidate.weight = Some(weight);
+        candidate.fate = if kept {
+            CandidateFate::Kept
+        } else {
+            CandidateFate::Killed
+        };
+    }
+}
@@ -1,5 +1,35 @@
+//! the core sampler is no_std + alloc, the "std" feature (default) adds caching,
+//! the biome registry and is required by the "serde", "fast-noise", "ffi", "python" and "bevy" features
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod algorithm;
 pub mod biome_picker;
+pub mod blend;
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod cache;
+pub mod cell_site;
+#[cfg(feature = "serde")]
+pub mod config;
 pub mod distance_fn;
+pub mod dither;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod hash;
+pub mod inspect;
+mod math;
+pub mod noise;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod region;
+#[cfg(feature = "std")]
+pub mod registry;
+pub mod scatter;
+pub mod seed;
+pub mod splat;
 pub mod utils;
 pub mod warp;
 pub mod worley;
@@ -8,7 +38,25 @@ pub mod worley;
 pub mod bevy;
 
 pub mod prelude {
+    pub use crate::algorithm::AlgorithmVersion;
+    pub use crate::biome_picker::AliasBiomePicker;
     pub use crate::biome_picker::BiomeVariants;
     pub use crate::biome_picker::SimpleBiomePicker;
+    pub use crate::blend::BiomeBlend;
+    pub use crate::builder::WorleyBuilder;
+    #[cfg(feature = "std")]
+    pub use crate::cache::{CacheSettings, CachedWorley};
+    pub use crate::cell_site::CellSite;
+    pub use crate::dither::DitherPattern;
+    pub use crate::error::WorleyError;
+    pub use crate::hash::HashVersion;
+    pub use crate::inspect::{Candidate, CandidateFate, SampleInspection};
+    pub use crate::noise::{Noise, NoiseFn, SimplexNoise, ValueNoise, WarpNoise};
+    pub use crate::region::Region;
+    #[cfg(feature = "std")]
+    pub use crate::registry::{BiomeId, BiomeRegistry};
+    pub use crate::scatter::{Placement, ScatterLayer, ScatterPattern};
+    pub use crate::seed::{SeedLayer, WorldSeed};
+    pub use crate::splat::{SplatFormat, SplatMap};
     pub use crate::worley::Worley;
 }
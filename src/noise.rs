@@ -0,0 +1,547 @@
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+
+#[cfg(feature = "bevy")]
+use bevy::reflect::{Reflect, std_traits::ReflectDefault};
+#[cfg(all(feature = "bevy", feature = "serde"))]
+use bevy::reflect::{ReflectDeserialize, ReflectSerialize};
+#[cfg(feature = "fast-noise")]
+use bracket_fast_noise::prelude::FastNoise;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::hash::portable_hash;
+use crate::math::floor;
+
+/// 2d noise used to warp coordinates, roughly -1.0 -> 1.0
+pub trait WarpNoise {
+    fn get_noise(&self, x: f32, z: f32) -> f32;
+}
+
+#[cfg(feature = "fast-noise")]
+impl WarpNoise for FastNoise {
+    fn get_noise(&self, x: f32, z: f32) -> f32 {
+        FastNoise::get_noise(self, x, z)
+    }
+}
+
+impl<N: WarpNoise + ?Sized> WarpNoise for &N {
+    fn get_noise(&self, x: f32, z: f32) -> f32 {
+        (**self).get_noise(x, z)
+    }
+}
+
+impl<N: WarpNoise + ?Sized> WarpNoise for Box<N> {
+    fn get_noise(&self, x: f32, z: f32) -> f32 {
+        (**self).get_noise(x, z)
+    }
+}
+
+/// any closure (or other noise crate) as warp noise
+/// ```ignore
+/// let noise = NoiseFn(|x, z| (x * 0.3).sin() * (z * 0.3).cos());
+/// ```
+#[derive(Debug, Copy, Clone, Default)]
+pub struct NoiseFn<F>(pub F);
+
+impl<F: Fn(f32, f32) -> f32> WarpNoise for NoiseFn<F> {
+    fn get_noise(&self, x: f32, z: f32) -> f32 {
+        (self.0)(x, z)
+    }
+}
+
+/// fractal value noise, no dependencies, same output on every platform
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+#[cfg_attr(feature = "bevy", derive(Reflect), reflect(Default))]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ValueNoise {
+    pub seed: u64,
+    pub frequency: f32,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+/// fractal simplex noise, no dependencies, same output on every platform
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+#[cfg_attr(feature = "bevy", derive(Reflect), reflect(Default))]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SimplexNoise {
+    pub seed: u64,
+    pub frequency: f32,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+impl Default for ValueNoise {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            frequency: 0.5,
+            octaves: 3,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+impl Default for SimplexNoise {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            frequency: 0.5,
+            octaves: 3,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+impl WarpNoise for ValueNoise {
+    fn get_noise(&self, x: f32, z: f32) -> f32 {
+        let fractal = Fractal::from_params(
+            self.seed,
+            self.frequency,
+            self.octaves,
+            self.lacunarity,
+            self.gain,
+        );
+        fractal.sample(x, z, value_2d)
+    }
+}
+
+impl WarpNoise for SimplexNoise {
+    fn get_noise(&self, x: f32, z: f32) -> f32 {
+        let fractal = Fractal::from_params(
+            self.seed,
+            self.frequency,
+            self.octaves,
+            self.lacunarity,
+            self.gain,
+        );
+        fractal.sample(x, z, simplex_2d)
+    }
+}
+
+// fractal brownian motion settings, shared by the built in noises
+struct Fractal {
+    seed: u64,
+    frequency: f64,
+    octaves: u32,
+    lacunarity: f64,
+    gain: f64,
+}
+
+impl Fractal {
+    fn from_params(seed: u64, frequency: f32, octaves: u32, lacunarity: f32, gain: f32) -> Self {
+        Self {
+            seed,
+            frequency: frequency as f64,
+            octaves: octaves.max(1),
+            lacunarity: lacunarity as f64,
+            gain: gain as f64,
+        }
+    }
+
+    fn sample(&self, x: f32, z: f32, noise: fn(u64, f64, f64) -> f64) -> f32 {
+        let (mut x, mut z) = (x as f64 * self.frequency, z as f64 * self.frequency);
+        let mut sum = 0.0;
+        let mut amplitude = 1.0;
+        let mut total_amplitude = 0.0;
+        for octave in 0..self.octaves {
+            sum += noise(self.seed.wrapping_add(octave as u64), x, z) * amplitude;
+            total_amplitude += amplitude;
+            amplitude *= self.gain;
+            x *= self.lacunarity;
+            z *= self.lacunarity;
+        }
+        if total_amplitude == 0.0 {
+            return 0.0;
+        }
+        (sum / total_amplitude) as f32
+    }
+}
+
+// -1.0 -> 1.0 for a lattice point
+#[inline(always)]
+fn lattice_value(seed: u64, x: i32, z: i32) -> f64 {
+    (portable_hash(seed, x, z) >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+}
+
+#[inline(always)]
+fn quintic(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn value_2d(seed: u64, x: f64, z: f64) -> f64 {
+    let (x0, z0) = (floor(x), floor(z));
+    let (tx, tz) = (quintic(x - x0), quintic(z - z0));
+    let (ix, iz) = (x0 as i32, z0 as i32);
+    let v00 = lattice_value(seed, ix, iz);
+    let v10 = lattice_value(seed, ix.wrapping_add(1), iz);
+    let v01 = lattice_value(seed, ix, iz.wrapping_add(1));
+    let v11 = lattice_value(seed, ix.wrapping_add(1), iz.wrapping_add(1));
+    let a = v00 + (v10 - v00) * tx;
+    let b = v01 + (v11 - v01) * tx;
+    a + (b - a) * tz
+}
+
+// 8 unit gradient directions, picked by lattice hash
+const GRADIENTS: [(f64, f64); 8] = [
+    (1.0, 0.0),
+    (-1.0, 0.0),
+    (0.0, 1.0),
+    (0.0, -1.0),
+    (
+        core::f64::consts::FRAC_1_SQRT_2,
+        core::f64::consts::FRAC_1_SQRT_2,
+    ),
+    (
+        -core::f64::consts::FRAC_1_SQRT_2,
+        core::f64::consts::FRAC_1_SQRT_2,
+    ),
+    (
+        core::f64::consts::FRAC_1_SQRT_2,
+        -core::f64::consts::FRAC_1_SQRT_2,
+    ),
+    (
+        -core::f64::consts::FRAC_1_SQRT_2,
+        -core::f64::consts::FRAC_1_SQRT_2,
+    ),
+];
+
+// (sqrt(3) - 1) / 2 and (3 - sqrt(3)) / 6
+const SKEW: f64 = 0.366_025_403_784_438_6;
+const UNSKEW: f64 = 0.211_324_865_405_187_1;
+
+// Stefan Gustavson, simplex noise demystified
+fn simplex_2d(seed: u64, x: f64, z: f64) -> f64 {
+    let s = (x + z) * SKEW;
+    let (i, j) = (floor(x + s), floor(z + s));
+    let t = (i + j) * UNSKEW;
+    let (x0, z0) = (x - (i - t), z - (j - t));
+    // which of the 2 triangles of the skewed cell
+    let (i1, j1) = if x0 > z0 { (1, 0) } else { (0, 1) };
+    let (x1, z1) = (x0 - i1 as f64 + UNSKEW, z0 - j1 as f64 + UNSKEW);
+    let (x2, z2) = (x0 - 1.0 + 2.0 * UNSKEW, z0 - 1.0 + 2.0 * UNSKEW);
+    let (i, j) = (i as i32, j as i32);
+
+    let corner = |ci: i32, cj: i32, dx: f64, dz: f64| {
+        let falloff = 0.5 - dx * dx - dz * dz;
+        if falloff <= 0.0 {
+            return 0.0;
+        }
+        let (gx, gz) = GRADIENTS[(portable_hash(seed, ci, cj) & 7) as usize];
+        let falloff = falloff * falloff;
+        falloff * falloff * (gx * dx + gz * dz)
+    };
+    let n = corner(i, j, x0, z0)
+        + corner(i.wrapping_add(i1), j.wrapping_add(j1), x1, z1)
+        + corner(i.wrapping_add(1), j.wrapping_add(1), x2, z2);
+    // scale to roughly -1.0 -> 1.0
+    n * 99.0
+}
+
+/// your own noise (a closure, another noise crate) in a Noise::Custom.
+/// clones share the same noise
+#[derive(Clone)]
+pub struct CustomNoise(Arc<dyn WarpNoise + Send + Sync>);
+
+impl CustomNoise {
+    pub fn new(noise: impl WarpNoise + Send + Sync + 'static) -> Self {
+        Self(Arc::new(noise))
+    }
+}
+
+impl WarpNoise for CustomNoise {
+    #[inline(always)]
+    fn get_noise(&self, x: f32, z: f32) -> f32 {
+        self.0.get_noise(x, z)
+    }
+}
+
+/// the noise used by WarpSettings.
+/// the default is Simplex, with or without the "fast-noise" feature.
+/// Custom noise can't be saved in configs.
+/// reflected as an opaque value, FastNoise keeps its seed, gain, jitter and perturb amplitude
+/// in private fields behind setters. scenes store it with "serde", the settings can't be
+/// reached by reflection paths, the inspectors edit it with the DebugPlugin's noise ui ("bevy_debug")
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", derive(Reflect), reflect(opaque, Clone, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+#[non_exhaustive]
+pub enum Noise {
+    Value(ValueNoise),
+    Simplex(SimplexNoise),
+    #[cfg(feature = "fast-noise")]
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_fast_noise"))]
+    FastNoise(FastNoise),
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(CustomNoise),
+}
+
+// FastNoise skips its permutation tables when serialized, they come back zeroed
+#[cfg(all(feature = "fast-noise", feature = "serde"))]
+pub(crate) fn deserialize_fast_noise<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<FastNoise, D::Error> {
+    FastNoise::deserialize(deserializer).map(reseed_fast_noise)
+}
+
+/// rebuild the permutation tables of a deserialized FastNoise
+#[cfg(all(feature = "fast-noise", feature = "serde"))]
+pub(crate) fn reseed_fast_noise(mut noise: FastNoise) -> FastNoise {
+    noise.set_seed(noise.get_seed());
+    noise
+}
+
+/// which variant a Noise is, without its settings
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum NoiseKind {
+    Value,
+    Simplex,
+    #[cfg(feature = "fast-noise")]
+    FastNoise,
+    Custom,
+}
+
+impl NoiseKind {
+    /// the kinds Noise::set_kind() can switch to with the enabled features
+    pub const BUILTIN: &'static [NoiseKind] = &[
+        NoiseKind::Value,
+        NoiseKind::Simplex,
+        #[cfg(feature = "fast-noise")]
+        NoiseKind::FastNoise,
+    ];
+}
+
+impl Default for Noise {
+    fn default() -> Self {
+        Noise::Simplex(SimplexNoise::default())
+    }
+}
+
+impl WarpNoise for Noise {
+    #[inline(always)]
+    fn get_noise(&self, x: f32, z: f32) -> f32 {
+        match self {
+            Noise::Value(noise) => noise.get_noise(x, z),
+            Noise::Simplex(noise) => noise.get_noise(x, z),
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(noise) => WarpNoise::get_noise(noise, x, z),
+            Noise::Custom(noise) => noise.get_noise(x, z),
+        }
+    }
+}
+
+impl From<ValueNoise> for Noise {
+    fn from(noise: ValueNoise) -> Self {
+        Noise::Value(noise)
+    }
+}
+
+impl From<SimplexNoise> for Noise {
+    fn from(noise: SimplexNoise) -> Self {
+        Noise::Simplex(noise)
+    }
+}
+
+impl Clone for Noise {
+    fn clone(&self) -> Self {
+        match self {
+            Noise::Value(noise) => Noise::Value(*noise),
+            Noise::Simplex(noise) => Noise::Simplex(*noise),
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(noise) => Noise::FastNoise(clone_fast_noise(noise)),
+            Noise::Custom(noise) => Noise::Custom(noise.clone()),
+        }
+    }
+}
+
+// FastNoise isn't Clone. public fields are copied, private ones go through their setters,
+// which also rebuild what FastNoise derives from them (permutation tables, fractal bounding)
+#[cfg(feature = "fast-noise")]
+fn clone_fast_noise(noise: &FastNoise) -> FastNoise {
+    let mut copy = FastNoise::seeded(noise.get_seed());
+    copy.frequency = noise.frequency;
+    copy.interp = noise.interp;
+    copy.noise_type = noise.noise_type;
+    copy.fractal_octaves = noise.fractal_octaves;
+    copy.fractal_lacunarity = noise.fractal_lacunarity;
+    copy.fractal_type = noise.fractal_type;
+    copy.cellular_distance_function = noise.cellular_distance_function;
+    copy.cellular_return_type = noise.cellular_return_type;
+    copy.set_fractal_gain(noise.get_fractal_gain());
+    let (i1, i2) = noise.get_cellular_distance_indices();
+    copy.set_cellular_distance_indices(i1, i2);
+    copy.set_cellular_jitter(noise.get_cellular_jitter());
+    copy.set_gradient_perterb_amp(noise.get_gradient_perterb_amp());
+    copy
+}
+
+#[cfg(feature = "fast-noise")]
+impl From<FastNoise> for Noise {
+    fn from(noise: FastNoise) -> Self {
+        Noise::FastNoise(noise)
+    }
+}
+
+impl From<CustomNoise> for Noise {
+    fn from(noise: CustomNoise) -> Self {
+        Noise::Custom(noise)
+    }
+}
+
+/// the fractal settings every builtin noise shares, tweakable without matching on the variant.
+/// Custom noise has none of them: getters return the Simplex defaults, setters do nothing
+impl Noise {
+    /// wrap your own noise, a closure can be passed with NoiseFn
+    pub fn custom(noise: impl WarpNoise + Send + Sync + 'static) -> Self {
+        Noise::Custom(CustomNoise::new(noise))
+    }
+
+    pub fn kind(&self) -> NoiseKind {
+        match self {
+            Noise::Value(_) => NoiseKind::Value,
+            Noise::Simplex(_) => NoiseKind::Simplex,
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(_) => NoiseKind::FastNoise,
+            Noise::Custom(_) => NoiseKind::Custom,
+        }
+    }
+
+    /// switch to another builtin noise, keeping the shared settings.
+    /// NoiseKind::Custom is ignored, use Noise::custom()
+    pub fn set_kind(&mut self, kind: NoiseKind) {
+        if self.kind() == kind {
+            return;
+        }
+        let (seed, frequency, octaves, lacunarity, gain) = (
+            self.seed(),
+            self.frequency(),
+            self.octaves(),
+            self.lacunarity(),
+            self.gain(),
+        );
+        *self = match kind {
+            NoiseKind::Value => Noise::Value(ValueNoise::default()),
+            NoiseKind::Simplex => Noise::Simplex(SimplexNoise::default()),
+            #[cfg(feature = "fast-noise")]
+            NoiseKind::FastNoise => Noise::FastNoise(FastNoise::default()),
+            NoiseKind::Custom => return,
+        };
+        self.set_seed(seed);
+        self.set_frequency(frequency);
+        self.set_octaves(octaves);
+        self.set_lacunarity(lacunarity);
+        self.set_gain(gain);
+    }
+
+    pub fn seed(&self) -> u64 {
+        match self {
+            Noise::Value(noise) => noise.seed,
+            Noise::Simplex(noise) => noise.seed,
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(noise) => noise.get_seed(),
+            Noise::Custom(_) => SimplexNoise::default().seed,
+        }
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        match self {
+            Noise::Value(noise) => noise.seed = seed,
+            Noise::Simplex(noise) => noise.seed = seed,
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(noise) => noise.set_seed(seed),
+            Noise::Custom(_) => {}
+        }
+    }
+
+    pub fn frequency(&self) -> f32 {
+        match self {
+            Noise::Value(noise) => noise.frequency,
+            Noise::Simplex(noise) => noise.frequency,
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(noise) => noise.get_frequency(),
+            Noise::Custom(_) => SimplexNoise::default().frequency,
+        }
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) {
+        match self {
+            Noise::Value(noise) => noise.frequency = frequency,
+            Noise::Simplex(noise) => noise.frequency = frequency,
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(noise) => noise.set_frequency(frequency),
+            Noise::Custom(_) => {}
+        }
+    }
+
+    pub fn octaves(&self) -> u32 {
+        match self {
+            Noise::Value(noise) => noise.octaves,
+            Noise::Simplex(noise) => noise.octaves,
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(noise) => noise.get_fractal_octaves().max(0) as u32,
+            Noise::Custom(_) => SimplexNoise::default().octaves,
+        }
+    }
+
+    pub fn set_octaves(&mut self, octaves: u32) {
+        match self {
+            Noise::Value(noise) => noise.octaves = octaves,
+            Noise::Simplex(noise) => noise.octaves = octaves,
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(noise) => {
+                noise.set_fractal_octaves(octaves.min(i32::MAX as u32) as i32)
+            }
+            Noise::Custom(_) => {}
+        }
+    }
+
+    pub fn lacunarity(&self) -> f32 {
+        match self {
+            Noise::Value(noise) => noise.lacunarity,
+            Noise::Simplex(noise) => noise.lacunarity,
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(noise) => noise.get_fractal_lacunarity(),
+            Noise::Custom(_) => SimplexNoise::default().lacunarity,
+        }
+    }
+
+    pub fn set_lacunarity(&mut self, lacunarity: f32) {
+        match self {
+            Noise::Value(noise) => noise.lacunarity = lacunarity,
+            Noise::Simplex(noise) => noise.lacunarity = lacunarity,
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(noise) => noise.set_fractal_lacunarity(lacunarity),
+            Noise::Custom(_) => {}
+        }
+    }
+
+    pub fn gain(&self) -> f32 {
+        match self {
+            Noise::Value(noise) => noise.gain,
+            Noise::Simplex(noise) => noise.gain,
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(noise) => noise.get_fractal_gain(),
+            Noise::Custom(_) => SimplexNoise::default().gain,
+        }
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        match self {
+            Noise::Value(noise) => noise.gain = gain,
+            Noise::Simplex(noise) => noise.gain = gain,
+            #[cfg(feature = "fast-noise")]
+            Noise::FastNoise(noise) => noise.set_fractal_gain(gain),
+            Noise::Custom(_) => {}
+        }
+    }
+}
@@ -0,0 +1,468 @@
+//! python bindings, for tuning configs and analyzing biome coverage from notebooks.
+//! build the extension module with
+//! `cargo rustc --release --lib --features python --crate-type cdylib`
+//! and copy target/release/libworley_biomes.so to worley_biomes.so (.pyd on windows)
+//!
+//! biomes are integer ids, the index of their name in the biome list (or 0..count).
+//! the biome list is process wide (BiomeRegistry::install_names()), every Worley
+//! in a python process has to use the same list
+use std::path::PathBuf;
+
+use numpy::ndarray::{Array2, Array3};
+use numpy::{IntoPyArray, PyArray2, PyArray3, PyReadonlyArray1};
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::algorithm::AlgorithmVersion;
+use crate::biome_picker::SimpleBiomePicker;
+use crate::blend::BiomeBlend;
+use crate::distance_fn::DistanceFn;
+use crate::noise::NoiseKind;
+use crate::region::Region;
+use crate::registry::{BiomeId, BiomeRegistry};
+use crate::worley::Worley;
+
+type IdWorley = Worley<BiomeId, SimpleBiomePicker<BiomeId>>;
+
+const DISTANCE_FNS: [DistanceFn; 5] = [
+    DistanceFn::Euclidean,
+    DistanceFn::EuclideanSquared,
+    DistanceFn::Manhattan,
+    DistanceFn::Chebyshev,
+    DistanceFn::Hybrid,
+];
+
+/// biome ids written for missing biomes: empty samples, unused weight slots
+const NO_BIOME: i32 = -1;
+
+/// the biome list: a count (biomes named "0", "1", ...) or the names in id order
+#[derive(FromPyObject)]
+enum Biomes {
+    Count(u16),
+    Names(Vec<String>),
+}
+
+impl Biomes {
+    fn install(self) -> PyResult<&'static BiomeRegistry> {
+        let names = match self {
+            Biomes::Count(count) => (0..count).map(|i| i.to_string()).collect(),
+            Biomes::Names(names) => names,
+        };
+        BiomeRegistry::install_names(&names).map_err(value_error)
+    }
+}
+
+fn value_error(err: impl ToString) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+// unknown names list the valid ones
+fn parse_name<T: Copy + std::fmt::Debug>(kind: &str, name: &str, options: &[T]) -> PyResult<T> {
+    options
+        .iter()
+        .copied()
+        .find(|option| format!("{option:?}") == name)
+        .ok_or_else(|| {
+            let known: Vec<String> = options.iter().map(|option| format!("{option:?}")).collect();
+            value_error(format!(
+                "unknown {kind} \"{name}\", expected one of {known:?}"
+            ))
+        })
+}
+
+fn biome_index(biome: Option<BiomeId>) -> i32 {
+    biome.map_or(NO_BIOME, |biome| biome.0 as i32)
+}
+
+/// Worley biomes with integer biome ids, same output as the rust Worley.
+/// settings are validated when set, invalid values raise ValueError
+#[pyclass(name = "Worley", module = "worley_biomes")]
+pub struct PyWorley {
+    worley: IdWorley,
+    registry: &'static BiomeRegistry,
+}
+
+impl PyWorley {
+    // set a field, put the old value back when the result is invalid
+    fn update<T>(&mut self, field: impl Fn(&mut IdWorley) -> &mut T, value: T) -> PyResult<()> {
+        let old = std::mem::replace(field(&mut self.worley), value);
+        if let Err(err) = self.worley.validate() {
+            *field(&mut self.worley) = old;
+            return Err(value_error(err));
+        }
+        Ok(())
+    }
+
+    fn region_samples(
+        &self,
+        region: (f64, f64, f64, f64),
+        width: usize,
+        depth: usize,
+    ) -> crate::region::SampleGrid<BiomeId> {
+        let (min_x, min_z, max_x, max_z) = region;
+        self.worley
+            .sample_region(Region::new((min_x, min_z), (max_x, max_z)), width, depth)
+    }
+}
+
+#[pymethods]
+impl PyWorley {
+    /// biomes: how many biomes (ids 0..count) or their names in id order
+    #[new]
+    #[pyo3(signature = (biomes, seed=0, zoom=100.0, sharpness=20.0, k=3))]
+    fn new(biomes: Biomes, seed: u64, zoom: f64, sharpness: f64, k: usize) -> PyResult<Self> {
+        let registry = biomes.install()?;
+        let worley = Worley::builder()
+            .seed(seed)
+            .zoom(zoom)
+            .sharpness(sharpness)
+            .k(k)
+            .build()
+            .map_err(value_error)?;
+        Ok(Self { worley, registry })
+    }
+
+    /// load a .worley.ron config of any version
+    #[staticmethod]
+    fn from_ron(ron: &str, biomes: Biomes) -> PyResult<Self> {
+        let registry = biomes.install()?;
+        let worley = Worley::from_ron(ron).map_err(value_error)?;
+        Ok(Self { worley, registry })
+    }
+
+    #[staticmethod]
+    fn load(path: PathBuf, biomes: Biomes) -> PyResult<Self> {
+        let ron = std::fs::read_to_string(&path)
+            .map_err(|err| PyIOError::new_err(format!("{}: {err}", path.display())))?;
+        Self::from_ron(&ron, biomes)
+    }
+
+    /// RON of the current config version, biomes are saved by name
+    fn to_ron(&self) -> PyResult<String> {
+        self.worley.to_ron().map_err(value_error)
+    }
+
+    fn save(&self, path: PathBuf) -> PyResult<()> {
+        std::fs::write(&path, self.to_ron()?)
+            .map_err(|err| PyIOError::new_err(format!("{}: {err}", path.display())))
+    }
+
+    /// biome names, indexed by biome id
+    #[getter]
+    fn biomes(&self) -> Vec<String> {
+        self.registry.names().to_vec()
+    }
+
+    fn biome_id(&self, name: &str) -> PyResult<u16> {
+        self.registry
+            .try_id(name)
+            .map(|id| id.0)
+            .map_err(value_error)
+    }
+
+    fn biome_name(&self, biome: u16) -> Option<&str> {
+        self.registry.name(BiomeId(biome))
+    }
+
+    /// (weight, biome) pairs at (x, z), the same order as Worley::get() in rust
+    fn sample(&self, x: f64, z: f64) -> Vec<(f64, u16)> {
+        self.worley
+            .get(x, z)
+            .iter()
+            .map(|(weight, biome)| (*weight, biome.0))
+            .collect()
+    }
+
+    /// strongest biome at (x, z)
+    fn dominant(&self, x: f64, z: f64) -> Option<u16> {
+        self.worley.get(x, z).dominant().map(|biome| biome.0)
+    }
+
+    /// one biome per block, see Worley::pick_dithered()
+    fn pick_dithered(&self, x: f64, z: f64) -> Option<u16> {
+        self.worley.pick_dithered(x, z).map(|biome| biome.0)
+    }
+
+    /// sample every (xs[i], zs[i]), returns (biomes, weights) arrays of shape (len, k).
+    /// unused slots are biome -1 with weight 0.0
+    #[allow(clippy::type_complexity)]
+    fn sample_points<'py>(
+        &self,
+        py: Python<'py>,
+        xs: PyReadonlyArray1<'py, f64>,
+        zs: PyReadonlyArray1<'py, f64>,
+    ) -> PyResult<(Bound<'py, PyArray2<i32>>, Bound<'py, PyArray2<f64>>)> {
+        let (xs, zs) = (xs.as_array(), zs.as_array());
+        if xs.len() != zs.len() {
+            return Err(value_error(format!(
+                "xs and zs have different lengths: {} and {}",
+                xs.len(),
+                zs.len()
+            )));
+        }
+        let k = self.worley.k;
+        let mut biomes = Array2::from_elem((xs.len(), k), NO_BIOME);
+        let mut weights = Array2::zeros((xs.len(), k));
+        for (i, (x, z)) in xs.iter().zip(zs.iter()).enumerate() {
+            for (slot, (weight, biome)) in self.worley.get(*x, *z).iter().enumerate().take(k) {
+                biomes[(i, slot)] = biome.0 as i32;
+                weights[(i, slot)] = *weight;
+            }
+        }
+        Ok((biomes.into_pyarray(py), weights.into_pyarray(py)))
+    }
+
+    /// sample a (width) * (depth) grid covering min -> max (max exclusive), see Worley::sample_region().
+    /// returns (biomes, weights) arrays of shape (depth, width, k), indexed [z, x, slot].
+    /// unused slots are biome -1 with weight 0.0
+    #[allow(clippy::type_complexity, clippy::too_many_arguments)]
+    fn sample_region<'py>(
+        &self,
+        py: Python<'py>,
+        min_x: f64,
+        min_z: f64,
+        max_x: f64,
+        max_z: f64,
+        width: usize,
+        depth: usize,
+    ) -> (Bound<'py, PyArray3<i32>>, Bound<'py, PyArray3<f64>>) {
+        let grid = self.region_samples((min_x, min_z, max_x, max_z), width, depth);
+        let k = self.worley.k;
+        let mut biomes = Array3::from_elem((depth, width, k), NO_BIOME);
+        let mut weights = Array3::zeros((depth, width, k));
+        for z in 0..depth {
+            for x in 0..width {
+                for (slot, (weight, biome)) in grid.get(x, z).iter().enumerate().take(k) {
+                    biomes[(z, x, slot)] = biome.0 as i32;
+                    weights[(z, x, slot)] = *weight;
+                }
+            }
+        }
+        (biomes.into_pyarray(py), weights.into_pyarray(py))
+    }
+
+    /// strongest biome of every sample, same grid as sample_region().
+    /// array of shape (depth, width), -1 where there is no biome
+    #[allow(clippy::too_many_arguments)]
+    fn dominant_region<'py>(
+        &self,
+        py: Python<'py>,
+        min_x: f64,
+        min_z: f64,
+        max_x: f64,
+        max_z: f64,
+        width: usize,
+        depth: usize,
+    ) -> Bound<'py, PyArray2<i32>> {
+        let grid = self.region_samples((min_x, min_z, max_x, max_z), width, depth);
+        Array2::from_shape_fn((depth, width), |(z, x)| {
+            biome_index(grid.get(x, z).dominant())
+        })
+        .into_pyarray(py)
+    }
+
+    #[getter]
+    fn seed(&self) -> u64 {
+        self.worley.seed
+    }
+
+    #[setter]
+    fn set_seed(&mut self, seed: u64) {
+        self.worley.seed = seed;
+    }
+
+    /// None: biomes are picked with seed
+    #[getter]
+    fn picker_seed(&self) -> Option<u64> {
+        self.worley.picker_seed
+    }
+
+    #[setter]
+    fn set_picker_seed(&mut self, seed: Option<u64>) {
+        self.worley.picker_seed = seed;
+    }
+
+    /// seed every layer from one world seed, text or a number, see WorldSeed
+    fn set_world_seed(&mut self, world_seed: &str) {
+        self.worley.set_world_seed(world_seed.into());
+    }
+
+    #[getter]
+    fn zoom(&self) -> f64 {
+        self.worley.zoom
+    }
+
+    #[setter]
+    fn set_zoom(&mut self, zoom: f64) -> PyResult<()> {
+        self.update(|w| &mut w.zoom, zoom)
+    }
+
+    #[getter]
+    fn sharpness(&self) -> f64 {
+        self.worley.sharpness
+    }
+
+    #[setter]
+    fn set_sharpness(&mut self, sharpness: f64) -> PyResult<()> {
+        self.update(|w| &mut w.sharpness, sharpness)
+    }
+
+    #[getter]
+    fn k(&self) -> usize {
+        self.worley.k
+    }
+
+    #[setter]
+    fn set_k(&mut self, k: usize) -> PyResult<()> {
+        self.update(|w| &mut w.k, k)
+    }
+
+    #[getter]
+    fn kill_percent_threshold(&self) -> Option<f64> {
+        self.worley.kill_percent_threshold
+    }
+
+    #[setter]
+    fn set_kill_percent_threshold(&mut self, threshold: Option<f64>) -> PyResult<()> {
+        self.update(|w| &mut w.kill_percent_threshold, threshold)
+    }
+
+    /// "Euclidean", "EuclideanSquared", "Manhattan", "Chebyshev" or "Hybrid"
+    #[getter]
+    fn distance_fn(&self) -> String {
+        format!("{:?}", self.worley.distance_fn_config)
+    }
+
+    #[setter]
+    fn set_distance_fn(&mut self, name: &str) -> PyResult<()> {
+        let distance_fn = parse_name("distance function", name, &DISTANCE_FNS)?;
+        self.worley.set_distance_fn(distance_fn);
+        Ok(())
+    }
+
+    /// "V0" or "V1", see AlgorithmVersion
+    #[getter]
+    fn algorithm(&self) -> String {
+        format!("{:?}", self.worley.algorithm)
+    }
+
+    #[setter]
+    fn set_algorithm(&mut self, name: &str) -> PyResult<()> {
+        self.worley.algorithm = parse_name("algorithm", name, &AlgorithmVersion::ALL)?;
+        Ok(())
+    }
+
+    /// biomes 0..count all have the same chance, the default
+    fn use_any(&mut self) -> PyResult<()> {
+        self.update(|w| &mut w.biome_picker, SimpleBiomePicker::Any)
+    }
+
+    /// only these biomes, all with the same chance
+    fn use_any_of(&mut self, biomes: Vec<u16>) -> PyResult<()> {
+        let biomes = biomes.into_iter().map(BiomeId).collect();
+        self.update(|w| &mut w.biome_picker, SimpleBiomePicker::AnyOf(biomes))
+    }
+
+    /// (biome, chance) pairs, the chances add up to 1.0
+    fn use_weighted(&mut self, weights: Vec<(u16, f32)>) -> PyResult<()> {
+        let weights = weights
+            .into_iter()
+            .map(|(biome, weight)| (BiomeId(biome), weight))
+            .collect();
+        self.update(
+            |w| &mut w.biome_picker,
+            SimpleBiomePicker::Weighted(weights),
+        )
+    }
+
+    #[getter]
+    fn warp_strength(&self) -> f32 {
+        self.worley.warp_settings.strength
+    }
+
+    #[setter]
+    fn set_warp_strength(&mut self, strength: f32) -> PyResult<()> {
+        self.update(|w| &mut w.warp_settings.strength, strength)
+    }
+
+    /// "Value", "Simplex" or "FastNoise" (with the fast-noise feature), "Custom" can't be set
+    #[getter]
+    fn warp_noise(&self) -> String {
+        format!("{:?}", self.worley.warp_settings.noise.kind())
+    }
+
+    #[setter]
+    fn set_warp_noise(&mut self, name: &str) -> PyResult<()> {
+        let kind = parse_name::<NoiseKind>("warp noise", name, NoiseKind::BUILTIN)?;
+        self.worley.warp_settings.noise.set_kind(kind);
+        Ok(())
+    }
+
+    #[getter]
+    fn warp_seed(&self) -> u64 {
+        self.worley.warp_settings.noise.seed()
+    }
+
+    #[setter]
+    fn set_warp_seed(&mut self, seed: u64) {
+        self.worley.warp_settings.noise.set_seed(seed);
+    }
+
+    #[getter]
+    fn warp_frequency(&self) -> f32 {
+        self.worley.warp_settings.noise.frequency()
+    }
+
+    #[setter]
+    fn set_warp_frequency(&mut self, frequency: f32) {
+        self.worley.warp_settings.noise.set_frequency(frequency);
+    }
+
+    #[getter]
+    fn warp_octaves(&self) -> u32 {
+        self.worley.warp_settings.noise.octaves()
+    }
+
+    #[setter]
+    fn set_warp_octaves(&mut self, octaves: u32) {
+        self.worley.warp_settings.noise.set_octaves(octaves);
+    }
+
+    #[getter]
+    fn warp_lacunarity(&self) -> f32 {
+        self.worley.warp_settings.noise.lacunarity()
+    }
+
+    #[setter]
+    fn set_warp_lacunarity(&mut self, lacunarity: f32) {
+        self.worley.warp_settings.noise.set_lacunarity(lacunarity);
+    }
+
+    #[getter]
+    fn warp_gain(&self) -> f32 {
+        self.worley.warp_settings.noise.gain()
+    }
+
+    #[setter]
+    fn set_warp_gain(&mut self, gain: f32) {
+        self.worley.warp_settings.noise.set_gain(gain);
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Worley(biomes={}, seed={}, zoom={}, sharpness={}, k={}, warp_strength={})",
+            self.registry.len(),
+            self.worley.seed,
+            self.worley.zoom,
+            self.worley.sharpness,
+            self.worley.k,
+            self.worley.warp_settings.strength,
+        )
+    }
+}
+
+#[pymodule]
+fn worley_biomes(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyWorley>()?;
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use alloc::vec::Vec;
+
+use tinyvec::TinyVec;
+
+/// axis aligned area in world coordinates, min is inclusive and max is exclusive
+/// so neighboring chunks never share a position
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Region {
+    pub min: (f64, f64),
+    pub max: (f64, f64),
+}
+
+impl Region {
+    pub fn new(min: (f64, f64), max: (f64, f64)) -> Self {
+        Self { min, max }
+    }
+
+    /// area covered by chunk (chunk_x, chunk_z), chunk 0,0 starts at world 0,0
+    pub fn chunk(chunk_x: i32, chunk_z: i32, chunk_size: f64) -> Self {
+        let min = (chunk_x as f64 * chunk_size, chunk_z as f64 * chunk_size);
+        Self {
+            min,
+            max: (min.0 + chunk_size, min.1 + chunk_size),
+        }
+    }
+
+    pub fn size(&self) -> (f64, f64) {
+        (self.max.0 - self.min.0, self.max.1 - self.min.1)
+    }
+
+    pub fn contains(&self, x: f64, z: f64) -> bool {
+        x >= self.min.0 && x < self.max.0 && z >= self.min.1 && z < self.max.1
+    }
+}
+
+/// biome weights sampled on a regular grid covering a region, see Worley::sample_region()
+#[derive(Debug, Clone)]
+pub struct SampleGrid<BiomeT: Default> {
+    pub region: Region,
+    pub width: usize,
+    pub depth: usize,
+    /// row major: index = z * width + x
+    pub samples: Vec<TinyVec<[(f64, BiomeT); 3]>>,
+}
+
+impl<BiomeT: Default> SampleGrid<BiomeT> {
+    /// weights of grid sample (x, z)
+    pub fn get(&self, x: usize, z: usize) -> &[(f64, BiomeT)] {
+        &self.samples[z * self.width + x]
+    }
+
+    /// world position of grid sample (x, z)
+    pub fn position(&self, x: usize, z: usize) -> (f64, f64) {
+        grid_position(self.region, self.width, self.depth, x, z)
+    }
+}
+
+/// world position of sample (x, z) when region is split into width * depth samples
+#[inline(always)]
+pub(crate) fn grid_position(
+    region: Region,
+    width: usize,
+    depth: usize,
+    x: usize,
+    z: usize,
+) -> (f64, f64) {
+    let (size_x, size_z) = region.size();
+    (
+        region.min.0 + x as f64 * size_x / width as f64,
+        region.min.1 + z as f64 * size_z / depth as f64,
+    )
+}
@@ -0,0 +1,298 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::RwLock;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::biome_picker::BiomeVariants;
+
+/// compact handle to a biome in a BiomeRegistry, for biomes loaded at runtime (mods, data files)
+/// instead of a rust enum.
+/// with the serde feature it (de)serializes as the biome name, looked up in the
+/// registry passed to BiomeRegistry::scope() or else the installed registry
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct BiomeId(pub u16);
+
+impl BiomeId {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// all variants of the installed registry, empty if none is installed.
+/// pickers return BiomeId::default() while nothing is installed, Worley::validate() fails
+impl BiomeVariants for BiomeId {
+    fn variants() -> &'static [Self] {
+        match BiomeRegistry::installed() {
+            Some(registry) => &registry.ids,
+            None => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    // a biome with this name is already registered
+    Duplicate(String),
+    // no more room for handles
+    Full,
+    // name not registered
+    Unknown { name: String, known: Vec<String> },
+    // failed to parse a registry file
+    Parse(String),
+    // BiomeRegistry::install_names() found a registry with other biomes installed
+    Installed(Vec<String>),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::Duplicate(name) => write!(f, "biome \"{name}\" is registered twice"),
+            RegistryError::Full => write!(f, "biome registry is full ({} biomes)", u16::MAX),
+            RegistryError::Unknown { name, known } => write!(
+                f,
+                "unknown biome \"{name}\", known biomes: [\"{}\"]",
+                known.join("\", \"")
+            ),
+            RegistryError::Parse(err) => write!(f, "failed to parse biome registry: {err}"),
+            RegistryError::Installed(installed) => write!(
+                f,
+                "a different biome registry is installed: [\"{}\"]",
+                installed.join("\", \"")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// string keyed biomes known at runtime, handed out as BiomeId handles
+///
+/// registry file (RON):
+/// ```ron
+/// (biomes: ["desert", "forest", "snow"])
+/// ```
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RegistryFile", into = "RegistryFile")
+)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BiomeRegistry {
+    names: Vec<String>,
+    ids: Vec<BiomeId>,
+    lookup: HashMap<String, BiomeId>,
+}
+
+// the installed registry, lives for the rest of the program
+static INSTALLED: RwLock<Option<&'static BiomeRegistry>> = RwLock::new(None);
+
+thread_local! {
+    // registry temporarily used by BiomeRegistry::scope()
+    static SCOPED: RefCell<Option<BiomeRegistry>> = const { RefCell::new(None) };
+}
+
+impl BiomeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// registry of biomes named "0", "1", ... for (count) biomes
+    pub fn numbered(count: u16) -> Self {
+        let mut registry = Self::new();
+        for i in 0..count {
+            // can't be a duplicate, or full
+            let _ = registry.register(&i.to_string());
+        }
+        registry
+    }
+
+    pub fn register(&mut self, name: &str) -> Result<BiomeId, RegistryError> {
+        if self.lookup.contains_key(name) {
+            return Err(RegistryError::Duplicate(name.to_string()));
+        }
+        let id = u16::try_from(self.names.len())
+            .map(BiomeId)
+            .map_err(|_| RegistryError::Full)?;
+        self.names.push(name.to_string());
+        self.ids.push(id);
+        self.lookup.insert(name.to_string(), id);
+        Ok(id)
+    }
+
+    pub fn id(&self, name: &str) -> Option<BiomeId> {
+        self.lookup.get(name).copied()
+    }
+
+    /// same as BiomeRegistry::id() with an error naming the known biomes
+    pub fn try_id(&self, name: &str) -> Result<BiomeId, RegistryError> {
+        self.id(name).ok_or_else(|| RegistryError::Unknown {
+            name: name.to_string(),
+            known: self.names.clone(),
+        })
+    }
+
+    pub fn name(&self, id: BiomeId) -> Option<&str> {
+        self.names.get(id.index()).map(|name| name.as_str())
+    }
+
+    pub fn ids(&self) -> &[BiomeId] {
+        &self.ids
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// make this registry the one BiomeId::variants() and serde use.
+    /// the registry is leaked, it has to outlive every BiomeId.
+    /// installing a registry with the same biomes again returns the installed one,
+    /// a different one replaces (and leaks) the previous one
+    pub fn install(self) -> &'static BiomeRegistry {
+        let mut installed = INSTALLED.write().expect("biome registry lock");
+        if let Some(registry) = *installed
+            && registry.names == self.names
+        {
+            return registry;
+        }
+        let registry: &'static BiomeRegistry = Box::leak(Box::new(self));
+        *installed = Some(registry);
+        registry
+    }
+
+    /// install a registry of (names), or reuse the installed one if it has the same names.
+    /// for bindings (ffi, python) where biome lists come from the caller, BiomeIds of
+    /// different registries can't mix so a different installed registry is an error
+    pub fn install_names<S: AsRef<str>>(
+        names: &[S],
+    ) -> Result<&'static BiomeRegistry, RegistryError> {
+        let mut installed = INSTALLED.write().expect("biome registry lock");
+        if let Some(registry) = *installed {
+            if registry
+                .names
+                .iter()
+                .map(String::as_str)
+                .eq(names.iter().map(AsRef::as_ref))
+            {
+                return Ok(registry);
+            }
+            return Err(RegistryError::Installed(registry.names.clone()));
+        }
+        let mut registry = Self::new();
+        for name in names {
+            registry.register(name.as_ref())?;
+        }
+        let registry: &'static BiomeRegistry = Box::leak(Box::new(registry));
+        *installed = Some(registry);
+        Ok(registry)
+    }
+
+    pub fn installed() -> Option<&'static BiomeRegistry> {
+        *INSTALLED.read().expect("biome registry lock")
+    }
+
+    /// use this registry for BiomeId (de)serialization inside (f) on this thread,
+    /// instead of the installed one
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        // restore the previous scope, even when (f) panics
+        struct Restore(Option<BiomeRegistry>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                SCOPED.with(|scoped| *scoped.borrow_mut() = self.0.take());
+            }
+        }
+        let _restore = Restore(SCOPED.with(|scoped| scoped.replace(Some(self.clone()))));
+        f()
+    }
+
+    // scoped registry, falling back to the installed one
+    #[cfg(feature = "serde")]
+    fn with_current<R>(f: impl FnOnce(Option<&BiomeRegistry>) -> R) -> R {
+        SCOPED.with(|scoped| match scoped.borrow().as_ref() {
+            Some(registry) => f(Some(registry)),
+            None => f(Self::installed()),
+        })
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_ron(ron: &str) -> Result<Self, RegistryError> {
+        ron::from_str(ron).map_err(|err| RegistryError::Parse(err.to_string()))
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct RegistryFile {
+    biomes: Vec<String>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RegistryFile> for BiomeRegistry {
+    type Error = RegistryError;
+
+    fn try_from(file: RegistryFile) -> Result<Self, Self::Error> {
+        let mut registry = BiomeRegistry::new();
+        for name in &file.biomes {
+            registry.register(name)?;
+        }
+        Ok(registry)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<BiomeRegistry> for RegistryFile {
+    fn from(registry: BiomeRegistry) -> Self {
+        Self {
+            biomes: registry.names,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for BiomeId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BiomeRegistry::with_current(|registry| match registry.and_then(|r| r.name(*self)) {
+            Some(name) => serializer.serialize_str(name),
+            // no name to write, keep the handle
+            None => serializer.serialize_u16(self.0),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for BiomeId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum NameOrIndex {
+            Name(String),
+            Index(u16),
+        }
+        let value = NameOrIndex::deserialize(deserializer)?;
+        BiomeRegistry::with_current(|registry| match (value, registry) {
+            (NameOrIndex::Name(name), Some(registry)) => {
+                registry.try_id(&name).map_err(serde::de::Error::custom)
+            }
+            (NameOrIndex::Name(name), None) => Err(serde::de::Error::custom(format!(
+                "can't look up biome \"{name}\", no BiomeRegistry installed or scoped"
+            ))),
+            (NameOrIndex::Index(index), Some(registry)) if index as usize >= registry.len() => {
+                Err(serde::de::Error::custom(format!(
+                    "biome index {index} out of range, registry has {} biomes",
+                    registry.len()
+                )))
+            }
+            (NameOrIndex::Index(index), _) => Ok(BiomeId(index)),
+        })
+    }
+}
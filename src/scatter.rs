@@ -0,0 +1,242 @@
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::biome_picker::{BiomePicker, BiomeVariants};
+use crate::blend::BiomeBlend;
+use crate::error::WorleyError;
+use crate::hash::HashVersion;
+use crate::math::{ceil, floor};
+use crate::region::Region;
+use crate::utils::unit_f64;
+use crate::worley::Worley;
+
+/// how candidate points are spread out before biome densities accept or reject them
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ScatterPattern {
+    // one candidate per grid cell of (spacing) size,
+    // moved randomly by (jitter) 0.0 -> 1.0 of the cell size
+    JitteredGrid { spacing: f64, jitter: f64 },
+    // candidates are never closer than (radius) to each other
+    PoissonDisk { radius: f64 },
+}
+
+impl ScatterPattern {
+    /// error for a spacing or radius that isn't finite and above 0, or a jitter outside 0.0 -> 1.0.
+    /// ScatterLayer::scatter() places nothing for those instead of looping (nearly) forever
+    pub fn validate(&self) -> Result<(), WorleyError> {
+        let positive = |value: f64| value.is_finite() && value > 0.0;
+        match *self {
+            ScatterPattern::JitteredGrid { spacing, .. } if !positive(spacing) => {
+                Err(WorleyError::InvalidScatterSpacing(spacing))
+            }
+            ScatterPattern::JitteredGrid { jitter, .. } if !(0.0..=1.0).contains(&jitter) => {
+                Err(WorleyError::InvalidScatterJitter(jitter))
+            }
+            ScatterPattern::PoissonDisk { radius } if !positive(radius) => {
+                Err(WorleyError::InvalidScatterRadius(radius))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // at most ONE candidate per grid cell
+    fn cell_size(&self) -> f64 {
+        match self {
+            ScatterPattern::JitteredGrid { spacing, .. } => *spacing,
+            ScatterPattern::PoissonDisk { radius } => radius / core::f64::consts::SQRT_2,
+        }
+    }
+}
+
+/// a placed item, returned from ScatterLayer::scatter()
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement<T> {
+    pub x: f64,
+    pub z: f64,
+    pub item: T,
+    /// stable per placement, use it for variation like rotation or scale
+    pub hash: u64,
+}
+
+/// deterministic decoration/structure placement (trees, rocks, villages),
+/// with per biome densities blended across biome borders.
+/// candidates live on a world space grid, so chunks can be generated in any order
+/// and placements line up seamlessly across chunk borders
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScatterLayer<T> {
+    pub pattern: ScatterPattern,
+    /// placements only depend on this seed, not on the Worley's seeds.
+    /// WorldSeed::decoration() derives one per layer from the world seed
+    pub seed: u64,
+    /// what this layer can place
+    pub items: Vec<T>,
+}
+
+const POSITION_SALT: u64 = 0;
+const PRIORITY_SALT: u64 = 1;
+const ROLL_SALT: u64 = 2;
+const VARIATION_SALT: u64 = 3;
+
+impl<T: Clone> ScatterLayer<T> {
+    pub fn new(pattern: ScatterPattern, seed: u64, items: Vec<T>) -> Result<Self, WorleyError> {
+        pattern.validate()?;
+        Ok(Self {
+            pattern,
+            seed,
+            items,
+        })
+    }
+
+    /// every placement inside region, seeded from ScatterLayer::seed
+    /// density: chance 0.0 -> 1.0 that a candidate becomes (item) inside (biome),
+    /// blended by the biome weights at the candidate position.
+    /// when the summed densities of all items exceed 1.0 they are scaled down to 1.0.
+    /// places nothing when the pattern fails ScatterPattern::validate() or region isn't finite
+    pub fn scatter<BiomeT, Picker>(
+        &self,
+        worley: &Worley<BiomeT, Picker>,
+        region: Region,
+        density: impl Fn(&BiomeT, &T) -> f64,
+    ) -> Vec<Placement<T>>
+    where
+        BiomeT: BiomeVariants + 'static + Default,
+        Picker: BiomePicker<BiomeT> + Default,
+    {
+        let hash = worley.algorithm.hash_version();
+        let seed = self.seed;
+        let mut placements = Vec::new();
+        let mut densities = Vec::with_capacity(self.items.len());
+        for (x, z, cell_x, cell_z) in self.candidates(hash, seed, region) {
+            let weights = worley.get(x, z);
+            densities.clear();
+            densities.extend(self.items.iter().map(|item| {
+                weights
+                    .blend(|biome| density(biome, item))
+                    .unwrap_or(0.0)
+                    .max(0.0)
+            }));
+            let total: f64 = densities.iter().sum();
+            if total <= 0.0 {
+                continue;
+            }
+
+            let roll = unit_f64(hash.hash(seed.wrapping_add(ROLL_SALT), cell_x, cell_z));
+            // more than 100% chance: scale the roll instead of the densities
+            let roll = roll * total.max(1.0);
+            let mut cumulative = 0.0;
+            for (item, density) in self.items.iter().zip(densities.iter()) {
+                cumulative += density;
+                if roll < cumulative {
+                    placements.push(Placement {
+                        x,
+                        z,
+                        item: item.clone(),
+                        hash: hash.hash(seed.wrapping_add(VARIATION_SALT), cell_x, cell_z),
+                    });
+                    break;
+                }
+            }
+        }
+        placements
+    }
+
+    /// (x, z, grid_x, grid_z) of candidates inside region, before densities are applied
+    fn candidates(
+        &self,
+        hash: HashVersion,
+        seed: u64,
+        region: Region,
+    ) -> Vec<(f64, f64, i32, i32)> {
+        let finite = [region.min.0, region.min.1, region.max.0, region.max.1]
+            .iter()
+            .all(|v| v.is_finite());
+        if self.pattern.validate().is_err() || !finite {
+            return Vec::new();
+        }
+        let cell_size = self.pattern.cell_size();
+        let min_x = floor(region.min.0 / cell_size) as i32;
+        let min_z = floor(region.min.1 / cell_size) as i32;
+        let max_x = floor(region.max.0 / cell_size) as i32;
+        let max_z = floor(region.max.1 / cell_size) as i32;
+
+        let mut out = Vec::new();
+        for cell_x in min_x..=max_x {
+            for cell_z in min_z..=max_z {
+                let (x, z) = self.candidate_point(hash, seed, cell_x, cell_z);
+                if !region.contains(x, z) {
+                    continue;
+                }
+                if let ScatterPattern::PoissonDisk { radius } = self.pattern
+                    && !self.wins_neighborhood(hash, seed, radius, cell_x, cell_z, x, z)
+                {
+                    continue;
+                }
+                out.push((x, z, cell_x, cell_z));
+            }
+        }
+        out
+    }
+
+    fn candidate_point(
+        &self,
+        hash: HashVersion,
+        seed: u64,
+        cell_x: i32,
+        cell_z: i32,
+    ) -> (f64, f64) {
+        let h = hash.hash(seed.wrapping_add(POSITION_SALT), cell_x, cell_z);
+        let u = (h & 0xFFFF_FFFF) as f64 / 4294967296.0;
+        let v = (h >> 32) as f64 / 4294967296.0;
+        let cell_size = self.pattern.cell_size();
+        let (u, v) = match self.pattern {
+            ScatterPattern::JitteredGrid { jitter, .. } => {
+                (0.5 + (u - 0.5) * jitter, 0.5 + (v - 0.5) * jitter)
+            }
+            ScatterPattern::PoissonDisk { .. } => (u, v),
+        };
+        (
+            (cell_x as f64 + u) * cell_size,
+            (cell_z as f64 + v) * cell_size,
+        )
+    }
+
+    // a poisson candidate survives if no higher priority candidate is within radius.
+    // only depends on neighboring grid cells, so every chunk agrees on the result
+    #[allow(clippy::too_many_arguments)]
+    fn wins_neighborhood(
+        &self,
+        hash: HashVersion,
+        seed: u64,
+        radius: f64,
+        cell_x: i32,
+        cell_z: i32,
+        x: f64,
+        z: f64,
+    ) -> bool {
+        let priority = |cx: i32, cz: i32| {
+            let h = hash.hash(seed.wrapping_add(PRIORITY_SALT), cx, cz);
+            // break ties by cell position
+            (h, cx, cz)
+        };
+        let own_priority = priority(cell_x, cell_z);
+        let reach = ceil(radius / self.pattern.cell_size()) as i32;
+        for dx in -reach..=reach {
+            for dz in -reach..=reach {
+                if dx == 0 && dz == 0 {
+                    continue;
+                }
+                let (cx, cz) = (cell_x + dx, cell_z + dz);
+                let (ox, oz) = self.candidate_point(hash, seed, cx, cz);
+                let too_close = (ox - x) * (ox - x) + (oz - z) * (oz - z) < radius * radius;
+                if too_close && priority(cx, cz) > own_priority {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,114 @@
+use core::convert::Infallible;
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::hash::{mix64, portable_hash_bytes};
+
+/// one seed for a whole world, every layer gets its own sub seed derived from it.
+/// a layer's sub seed only depends on the world seed and the layer's label,
+/// so reseeding one layer never reshuffles the others
+/// ```ignore
+/// let world = WorldSeed::from("mossy hills");
+/// worley.set_world_seed(world);
+/// let trees = ScatterLayer::new(pattern, world.decoration("trees"), items)?;
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct WorldSeed(pub u64);
+
+/// the layers of a Worley, see WorldSeed::layer()
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SeedLayer {
+    // Worley::picker_seed, which biome each cell gets
+    Picker,
+    // Worley::seed, where the cells' feature points are
+    FeaturePoints,
+    // seed of the warp noise
+    Warp,
+    // ScatterLayer seeds, one per layer, see WorldSeed::decoration()
+    Decoration,
+}
+
+impl SeedLayer {
+    pub const ALL: [SeedLayer; 4] = [
+        SeedLayer::Picker,
+        SeedLayer::FeaturePoints,
+        SeedLayer::Warp,
+        SeedLayer::Decoration,
+    ];
+
+    /// the label the layer's sub seed is derived with, never changes
+    pub fn label(&self) -> &'static str {
+        match self {
+            SeedLayer::Picker => "picker",
+            SeedLayer::FeaturePoints => "feature_points",
+            SeedLayer::Warp => "warp",
+            SeedLayer::Decoration => "decoration",
+        }
+    }
+}
+
+impl WorldSeed {
+    /// seed typed by a person: whole numbers ("42", "-7") are used as is,
+    /// anything else is hashed with portable_hash_bytes(), same seed on every platform.
+    /// surrounding whitespace is ignored
+    pub fn from_text(text: &str) -> Self {
+        let text = text.trim();
+        if let Ok(seed) = text.parse::<u64>() {
+            return WorldSeed(seed);
+        }
+        if let Ok(seed) = text.parse::<i64>() {
+            return WorldSeed(seed as u64);
+        }
+        WorldSeed(portable_hash_bytes(text.as_bytes()))
+    }
+
+    /// independent sub seed for (label), derive again for deeper levels:
+    /// world.derive("decoration").derive("trees")
+    pub fn derive(&self, label: &str) -> WorldSeed {
+        WorldSeed(mix64(
+            mix64(self.0 ^ DERIVE_SALT) ^ portable_hash_bytes(label.as_bytes()),
+        ))
+    }
+
+    /// sub seed of a Worley layer
+    pub fn layer(&self, layer: SeedLayer) -> u64 {
+        self.derive(layer.label()).0
+    }
+
+    /// seed of the ScatterLayer called (name), derived from the Decoration layer
+    pub fn decoration(&self, name: &str) -> u64 {
+        self.derive(SeedLayer::Decoration.label()).derive(name).0
+    }
+}
+
+const DERIVE_SALT: u64 = 0x6A09_E667_F3BC_C908;
+
+impl From<u64> for WorldSeed {
+    fn from(seed: u64) -> Self {
+        WorldSeed(seed)
+    }
+}
+
+impl From<&str> for WorldSeed {
+    fn from(text: &str) -> Self {
+        WorldSeed::from_text(text)
+    }
+}
+
+impl FromStr for WorldSeed {
+    type Err = Infallible;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Ok(WorldSeed::from_text(text))
+    }
+}
+
+impl fmt::Display for WorldSeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
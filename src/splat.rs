@@ -0,0 +1,133 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::biome_picker::BiomeVariants;
+use crate::math::floor;
+use crate::region::SampleGrid;
+
+/// how biome weights are packed into the RGBA8 texels of a SplatMap.
+/// biomes are numbered by their position in BiomeVariants::variants(), biomes that aren't listed are left out
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum SplatFormat {
+    /// one channel per biome, biome i is channel i % 4 of layer i / 4.
+    /// use the layers as separate textures or as one texture array
+    #[default]
+    Channels,
+    /// one layer for up to 256 biomes: (index of the strongest biome, index of the second,
+    /// weight of the strongest, weight of the second), weaker biomes are dropped and the
+    /// two weights are rescaled to add up to 255. a lone biome uses the same index twice
+    IndexWeight,
+}
+
+/// biome weights of a SampleGrid rendered into RGBA8 images for terrain shaders,
+/// texel (x, z) of a layer is at byte (z * width + x) * 4
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplatMap {
+    pub format: SplatFormat,
+    pub width: usize,
+    pub depth: usize,
+    pub layers: Vec<Vec<u8>>,
+}
+
+impl SplatMap {
+    pub fn new<BiomeT>(grid: &SampleGrid<BiomeT>, format: SplatFormat) -> Self
+    where
+        BiomeT: BiomeVariants + PartialEq + Default + 'static,
+    {
+        let layers = match format {
+            SplatFormat::Channels => channel_layers(grid),
+            SplatFormat::IndexWeight => vec![index_weight_layer(grid)],
+        };
+        Self {
+            format,
+            width: grid.width,
+            depth: grid.depth,
+            layers,
+        }
+    }
+
+    /// RGBA8 value of texel (x, z) in (layer)
+    pub fn texel(&self, layer: usize, x: usize, z: usize) -> [u8; 4] {
+        let i = (z * self.width + x) * 4;
+        let mut texel = [0; 4];
+        texel.copy_from_slice(&self.layers[layer][i..i + 4]);
+        texel
+    }
+
+    /// every layer after each other, the layout of a texture array
+    pub fn array_data(&self) -> Vec<u8> {
+        self.layers.concat()
+    }
+}
+
+fn biome_index<BiomeT: BiomeVariants + PartialEq + 'static>(biome: BiomeT) -> Option<usize> {
+    BiomeT::variants()
+        .iter()
+        .position(|variant| *variant == biome)
+}
+
+fn to_u8(weight: f64) -> u8 {
+    floor(weight.clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+}
+
+fn channel_layers<BiomeT>(grid: &SampleGrid<BiomeT>) -> Vec<Vec<u8>>
+where
+    BiomeT: BiomeVariants + PartialEq + Default + 'static,
+{
+    let layer_count = BiomeT::variants().len().div_ceil(4);
+    let texels = grid.width * grid.depth;
+    let mut layers = vec![vec![0u8; texels * 4]; layer_count];
+    for (texel, weights) in grid.samples.iter().enumerate() {
+        for (weight, biome) in weights.iter() {
+            if let Some(i) = biome_index(*biome) {
+                layers[i / 4][texel * 4 + i % 4] = to_u8(*weight);
+            }
+        }
+    }
+    layers
+}
+
+fn index_weight_layer<BiomeT>(grid: &SampleGrid<BiomeT>) -> Vec<u8>
+where
+    BiomeT: BiomeVariants + PartialEq + Default + 'static,
+{
+    let mut layer = Vec::with_capacity(grid.width * grid.depth * 4);
+    for weights in grid.samples.iter() {
+        // the two strongest biomes, in order
+        let mut strongest: [Option<(f64, u8)>; 2] = [None, None];
+        for (weight, biome) in weights.iter() {
+            let Some(i) = biome_index(*biome).filter(|i| *i <= u8::MAX as usize) else {
+                continue;
+            };
+            let entry = Some((*weight, i as u8));
+            match strongest {
+                [None, _] => strongest[0] = entry,
+                [Some((first, _)), _] if *weight > first => {
+                    strongest = [entry, strongest[0]];
+                }
+                [_, None] => strongest[1] = entry,
+                [_, Some((second, _))] if *weight > second => strongest[1] = entry,
+                _ => {}
+            }
+        }
+        let texel = match strongest {
+            [None, _] => [0, 0, 0, 0],
+            [Some((_, index)), None] => [index, index, 255, 0],
+            [Some((first, index_a)), Some((second, index_b))] => {
+                let total = first + second;
+                let weight_a = if total > 0.0 {
+                    to_u8(first / total)
+                } else {
+                    255
+                };
+                [index_a, index_b, weight_a, 255 - weight_a]
+            }
+        };
+        layer.extend_from_slice(&texel);
+    }
+    layer
+}
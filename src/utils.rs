@@ -1,13 +1,79 @@
-use rand::{Rng, SeedableRng, rngs::StdRng};
-use std::hash::{Hash, Hasher};
+use core::hash::{Hash, Hasher};
+
+use rand::{SeedableRng, rngs::StdRng};
 
 pub fn hash_u64(seed: u64, x: i32, z: i32) -> u64 {
-    let mut hasher = fxhash::FxHasher::default();
+    let mut hasher = FxHasher::default();
     (seed, x, z).hash(&mut hasher);
     hasher.finish()
 }
 
-pub fn seeded_rng(seed: u64, x: i32, z: i32) -> impl Rng {
+pub fn seeded_rng(seed: u64, x: i32, z: i32) -> StdRng {
     let combined = seed ^ ((x as u64) << 32) ^ (z as u64);
     StdRng::seed_from_u64(combined)
 }
+
+/// map a hash to 0.0..1.0 (exclusive), uses the top 53 bits
+#[inline(always)]
+pub fn unit_f64(hash: u64) -> f64 {
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// rustc's FxHasher, hashes integers exactly like the fxhash 0.2 crate (HashVersion::V0).
+/// works on usize words, so 32 and 64 bit targets hash differently
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FxHasher {
+    hash: usize,
+}
+
+#[cfg(target_pointer_width = "64")]
+const FX_SEED: usize = 0x517c_c1b7_2722_0a95_u64 as usize;
+#[cfg(not(target_pointer_width = "64"))]
+const FX_SEED: usize = 0x2722_0a95;
+
+impl FxHasher {
+    #[inline(always)]
+    fn add_word(&mut self, word: usize) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(FX_SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(size_of::<usize>());
+        for chunk in &mut chunks {
+            let mut word = [0u8; size_of::<usize>()];
+            word.copy_from_slice(chunk);
+            self.add_word(usize::from_ne_bytes(word));
+        }
+        for &byte in chunks.remainder() {
+            self.add_word(byte as usize);
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add_word(i as usize);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add_word(i as usize);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add_word(i as usize);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add_word(i as usize);
+        #[cfg(not(target_pointer_width = "64"))]
+        self.add_word((i >> 32) as usize);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add_word(i);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash as u64
+    }
+}
@@ -1,27 +1,85 @@
-use bracket_fast_noise::prelude::FastNoise;
+#[cfg(feature = "bevy")]
+use bevy::reflect::{Reflect, std_traits::ReflectDefault};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Default)]
+use crate::math::sqrt;
+use crate::noise::{Noise, WarpNoise};
+
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "crate::config::WarpSettingsRepr")
+)]
+#[cfg_attr(feature = "bevy", derive(Reflect), reflect(Default))]
+#[derive(Default, Clone)]
 pub struct WarpSettings {
     pub strength: f32,
-    pub noise: FastNoise,
+    pub noise: Noise,
 }
 
 impl WarpSettings {
     pub fn warp_coords(&self, x: f32, z: f32) -> (f64, f64) {
-        let nx = self.noise.get_noise(x, z);
-        let nz = self.noise.get_noise(x + 103f32, z);
-        (
-            (x + nx * self.strength) as f64,
-            (z + nz * self.strength) as f64,
-        )
+        warp_coords(&self.noise, self.strength, x, z)
     }
 }
 
-pub fn warp_coords(noise: &FastNoise, strength: f32, x: f32, z: f32) -> (f64, f64) {
+pub fn warp_coords<N: WarpNoise + ?Sized>(noise: &N, strength: f32, x: f32, z: f32) -> (f64, f64) {
     let nx = noise.get_noise(x, z);
     let nz = noise.get_noise(x + 103f32, z);
     ((x + nx * strength) as f64, (z + nz * strength) as f64)
 }
+
+/// approximate inverse of warp_coords(), finds the position that warps onto (x, z).
+/// Newton's method on the warp, falling back to damped fixed point steps where the warp folds.
+/// converges for warps that can be inverted, to about f32 precision of the noise input.
+/// for strong warps several positions can warp onto the same point, one of them is returned
+/// deterministically, and some points have no position warping onto them at all:
+/// then the closest guess is returned, check it with warp_coords() when that matters
+pub fn unwarp_coords<N: WarpNoise + ?Sized>(
+    noise: &N,
+    strength: f32,
+    x: f64,
+    z: f64,
+) -> (f64, f64) {
+    let (mut px, mut pz) = (x, z);
+    let mut best = (px, pz, f64::MAX);
+    for _ in 0..UNWARP_ITERATIONS {
+        let (wx, wz) = warp_coords(noise, strength, px as f32, pz as f32);
+        let (ex, ez) = (x - wx, z - wz);
+        let error = ex * ex + ez * ez;
+        if error < best.2 {
+            best = (px, pz, error);
+        }
+        if error < UNWARP_TOLERANCE {
+            break;
+        }
+        // jacobian of the warp from finite differences
+        let (ax, az) = warp_coords(noise, strength, (px + UNWARP_DELTA) as f32, pz as f32);
+        let (bx, bz) = warp_coords(noise, strength, px as f32, (pz + UNWARP_DELTA) as f32);
+        let (j11, j21) = ((ax - wx) / UNWARP_DELTA, (az - wz) / UNWARP_DELTA);
+        let (j12, j22) = ((bx - wx) / UNWARP_DELTA, (bz - wz) / UNWARP_DELTA);
+        let det = j11 * j22 - j12 * j21;
+        let (mut sx, mut sz) = if det.abs() > UNWARP_MIN_DET {
+            ((j22 * ex - j12 * ez) / det, (j11 * ez - j21 * ex) / det)
+        } else {
+            (ex * UNWARP_STEP, ez * UNWARP_STEP)
+        };
+        // near folds newton overshoots, never step further than the warp can move a point
+        let length = sqrt(sx * sx + sz * sz);
+        let max_step = strength.abs() as f64 + UNWARP_STEP;
+        if length > max_step {
+            sx *= max_step / length;
+            sz *= max_step / length;
+        }
+        px += sx;
+        pz += sz;
+    }
+    (best.0, best.1)
+}
+
+const UNWARP_ITERATIONS: usize = 32;
+const UNWARP_STEP: f64 = 0.5;
+const UNWARP_TOLERANCE: f64 = 1e-10;
+const UNWARP_DELTA: f64 = 1e-3;
+const UNWARP_MIN_DET: f64 = 1e-3;
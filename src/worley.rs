@@ -1,25 +1,44 @@
-use std::default::Default;
-use std::marker::PhantomData;
+use alloc::vec::Vec;
+use core::default::Default;
+use core::marker::PhantomData;
 
+#[cfg(feature = "bevy")]
+use bevy::reflect::{
+    FromReflect, PartialReflect, Reflect, ReflectFromReflect, std_traits::ReflectDefault,
+};
 #[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 
 use tinyvec::TinyVec;
 
+use crate::algorithm::AlgorithmVersion;
 use crate::biome_picker::{BiomePicker, BiomeVariants};
+use crate::blend::BiomeBlend;
 use crate::distance_fn::DistanceFn;
-use crate::utils::hash_u64;
+use crate::dither::DitherPattern;
+use crate::error::WorleyError;
+use crate::math::floor;
+use crate::noise::NoiseKind;
+use crate::region::{Region, SampleGrid, grid_position};
+use crate::seed::{SeedLayer, WorldSeed};
 use crate::warp::{WarpSettings, warp_coords};
 
 ///! a biome picker based on (worley) which is offset by (noise)
+///! serialized in the versioned format of config.rs, older files are migrated when loading
 #[cfg_attr(
     feature = "serde",
-    derive(Serialize, Deserialize),
-    serde(bound(
-        serialize = "BiomeT: Serialize, Picker: Serialize",
-        deserialize = "BiomeT: Deserialize<'de>, Picker: Deserialize<'de>"
-    ))
+    derive(Deserialize),
+    serde(
+        try_from = "crate::config::WorleyRepr<Picker>",
+        bound(deserialize = "BiomeT: Deserialize<'de>, Picker: Deserialize<'de>")
+    )
 )]
+#[cfg_attr(
+    feature = "bevy",
+    derive(Reflect),
+    reflect(Default, FromReflect, from_reflect = false)
+)]
+#[derive(Clone)]
 pub struct Worley<BiomeT, Picker>
 where
     BiomeT: BiomeVariants,
@@ -28,29 +47,31 @@ where
     ///! biome picking
     pub biome_picker: Picker,
     pub zoom: f64,
-    #[cfg_attr(feature = "serde", serde(skip, default = "default_distance_fn"))]
+    // reflection only sees distance_fn_config, from_reflect() rebuilds distance_fn from it
+    // and WorleyReflectPlugin keeps them in sync for edits applied in place
+    #[cfg_attr(feature = "bevy", reflect(ignore))]
     pub distance_fn: fn(f64, f64) -> f64,
     pub distance_fn_config: DistanceFn,
     ///! high value: sharper borders, recommended: 0.0 -> 20.0
     pub sharpness: f64,
     ///! how many k biomes to fetch closest
     pub k: usize,
+    ///! feature point seed, also picks biomes when picker_seed is None
     pub seed: u64,
+    ///! biome picking seed, lets biomes be rerolled without moving the cells
+    pub picker_seed: Option<u64>,
+    ///! pins the output of a seed, older versions keep old worlds unchanged
+    pub algorithm: AlgorithmVersion,
     ///! warps coordinate for interesting shapes
     pub warp_settings: WarpSettings,
     ///! if set, biomes below this threshold, will not return from Worley::get()
+    ///! the strongest biome is always kept, has to be 0.0 -> below 1.0
     ///! recommended to be set, defaults to 0.01 = 1%
     pub kill_percent_threshold: Option<f64>,
-    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "bevy", reflect(ignore))]
     pub _phantom: PhantomData<BiomeT>,
 }
 
-// euclidian squared
-#[cfg(feature = "serde")]
-fn default_distance_fn() -> fn(f64, f64) -> f64 {
-    |dx, dz| dx * dx + dz * dz
-}
-
 impl<BiomeT, Picker> Default for Worley<BiomeT, Picker>
 where
     BiomeT: BiomeVariants,
@@ -70,11 +91,32 @@ where
             _phantom: PhantomData::default(),
             kill_percent_threshold: Some(0.01),
             seed: 0,
+            picker_seed: None,
+            algorithm: AlgorithmVersion::default(),
         }
     }
 }
 
-const NEIGHBOR_OFFSETS: [(i32, i32); 9] = [
+// scenes and reflected components build their Worley here, distance_fn has to follow the config
+#[cfg(feature = "bevy")]
+impl<BiomeT, Picker> FromReflect for Worley<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants,
+    Picker: BiomePicker<BiomeT> + Default,
+    Self: Reflect,
+{
+    fn from_reflect(reflect: &dyn PartialReflect) -> Option<Self> {
+        let mut worley = Self::default();
+        worley.try_apply(reflect).ok()?;
+        worley.distance_fn = worley.distance_fn_config.to_func();
+        Some(worley)
+    }
+}
+
+/// biggest valid Worley::k, every neighboring cell
+pub const MAX_K: usize = NEIGHBOR_OFFSETS.len();
+
+pub(crate) const NEIGHBOR_OFFSETS: [(i32, i32); 9] = [
     (-1, -1),
     (-1, 0),
     (-1, 1),
@@ -86,6 +128,55 @@ const NEIGHBOR_OFFSETS: [(i32, i32); 9] = [
     (1, 1),
 ];
 
+impl<BiomeT, Picker> Worley<BiomeT, Picker>
+where
+    BiomeT: BiomeVariants,
+    Picker: BiomePicker<BiomeT> + Default,
+{
+    /// check for settings that panic or produce NaNs when sampled.
+    /// deserialized worleys are validated when loading
+    pub fn validate(&self) -> Result<(), WorleyError> {
+        self.biome_picker.validate()?;
+        if self.k == 0 || self.k > MAX_K {
+            return Err(WorleyError::InvalidK(self.k));
+        }
+        if !self.zoom.is_finite() || self.zoom <= 0.0 {
+            return Err(WorleyError::InvalidZoom(self.zoom));
+        }
+        if !self.sharpness.is_finite() {
+            return Err(WorleyError::InvalidSharpness(self.sharpness));
+        }
+        if let Some(threshold) = self.kill_percent_threshold
+            && !(0.0..1.0).contains(&threshold)
+        {
+            return Err(WorleyError::InvalidKillThreshold(threshold));
+        }
+        if !self.warp_settings.strength.is_finite() {
+            return Err(WorleyError::InvalidWarpStrength(
+                self.warp_settings.strength,
+            ));
+        }
+        Ok(())
+    }
+
+    /// seed biomes are picked with
+    pub fn picker_seed(&self) -> u64 {
+        self.picker_seed.unwrap_or(self.seed)
+    }
+
+    /// seed every layer (feature points, biome picking, warp noise) from one world seed.
+    /// Noise::Custom has no seed and keeps warping the same, returns false when the warp
+    /// noise wasn't reseeded
+    pub fn set_world_seed(&mut self, world_seed: WorldSeed) -> bool {
+        self.seed = world_seed.layer(SeedLayer::FeaturePoints);
+        self.picker_seed = Some(world_seed.layer(SeedLayer::Picker));
+        self.warp_settings
+            .noise
+            .set_seed(world_seed.layer(SeedLayer::Warp));
+        self.warp_settings.noise.kind() != NoiseKind::Custom
+    }
+}
+
 impl<BiomeT, Picker> Worley<BiomeT, Picker>
 where
     BiomeT: BiomeVariants + 'static + Default,
@@ -101,6 +192,33 @@ where
 
     ///! returns a vec of (0: percentage) we use for (1: biome type)
     pub fn get(&self, x: f64, z: f64) -> TinyVec<[(f64, BiomeT); 3]> {
+        self.get_with_cells(x, z, |cell_x, cell_z| self.cell(cell_x, cell_z), &mut ())
+    }
+
+    /// feature point + biome of a cell
+    #[inline(always)]
+    pub(crate) fn cell(&self, cell_x: i32, cell_z: i32) -> ((f64, f64), BiomeT) {
+        (
+            self.algorithm.cell_point(self.seed, cell_x, cell_z),
+            self.biome_picker.pick_biome_versioned(
+                self.picker_seed(),
+                cell_x,
+                cell_z,
+                self.algorithm.hash_version(),
+            ),
+        )
+    }
+
+    /// Worley::get() where cell lookups go through (cell), lets a cache skip the hashing.
+    /// (observer) sees every step, Worley::inspect() records them
+    #[inline(always)]
+    pub(crate) fn get_with_cells(
+        &self,
+        x: f64,
+        z: f64,
+        mut cell: impl FnMut(i32, i32) -> ((f64, f64), BiomeT),
+        observer: &mut impl SampleObserver<BiomeT>,
+    ) -> TinyVec<[(f64, BiomeT); 3]> {
         let (x, z) = (x / self.zoom, z / self.zoom);
         let (x, z) = warp_coords(
             &self.warp_settings.noise,
@@ -109,65 +227,132 @@ where
             z as f32,
         );
 
-        let cell_x = x.floor() as i32;
-        let cell_z = z.floor() as i32;
+        let cell_x = floor(x) as i32;
+        let cell_z = floor(z) as i32;
+        observer.position((x, z), (cell_x, cell_z));
 
-        let mut candidates: [(f64, BiomeT); 9] = [(0.0, BiomeT::default()); 9];
+        // (distance, neighbor index), the selection only compares distances
+        let mut candidates: [(f64, usize); MAX_K] = [(0.0, 0); MAX_K];
+        let mut biomes: [BiomeT; MAX_K] = [BiomeT::default(); MAX_K];
         for (i, (dx, dz)) in NEIGHBOR_OFFSETS.iter().enumerate() {
-            let cx = cell_x + dx;
-            let cz = cell_z + dz;
-            let (fx, fz) = cell_point(self.seed, cx, cz);
+            let neighbor = (cell_x + dx, cell_z + dz);
+            let ((fx, fz), biome) = cell(neighbor.0, neighbor.1);
             let dist = (self.distance_fn)(x - fx, z - fz);
-            let biome = self.biome_picker.pick_biome(self.seed, cx, cz);
-            candidates[i] = (dist, biome);
+            observer.candidate(i, neighbor, (fx, fz), dist, biome);
+            candidates[i] = (dist, i);
+            biomes[i] = biome;
         }
 
         let k = self.k.min(candidates.len());
-        // select the 3 lowest
-        candidates.select_nth_unstable_by(k, |a, b| a.0.total_cmp(&b.0));
+        // select the k lowest
+        self.algorithm.select_nearest(&mut candidates, k);
+        let nearest = &candidates[..k];
 
         let mut sum = 0.0;
         let mut out = TinyVec::with_capacity(self.k);
-        for (d, biome) in candidates.iter().take(self.k) {
-            // very close, high value
-            let w = if *d < 1e-9 {
-                100.0
-            } else {
-                // closer to 0, higher weight value
-                1.0 / d.powf(self.sharpness)
-            };
+        for (d, i) in nearest {
+            let w = self.algorithm.weight(*d, self.sharpness);
             sum += w;
-            out.push((w, *biome));
+            out.push((w, biomes[*i]));
         }
 
         for (w, _) in out.iter_mut() {
             *w /= sum;
         }
 
-        // remove low percentage biomes
-        if let Some(kill_percent_threshold) = self.kill_percent_threshold {
-            let len_before = out.len();
-            out.retain(|(percent, _biome)| *percent > kill_percent_threshold);
-            if out.len() != len_before {
-                // calculate new sum, and recalculate the percentages
-                let new_sum_percent: f64 = out.iter().map(|(percent, _biome)| percent).sum();
-                for (percent, _biome) in out.iter_mut() {
-                    *percent /= new_sum_percent;
-                }
+        // remove low percentage biomes, the strongest biome always survives,
+        // so get() never returns nothing
+        let strongest = strongest_weight(&out);
+        let keep = |percent: f64| match self.kill_percent_threshold {
+            Some(kill_percent_threshold) => {
+                percent > kill_percent_threshold || percent == strongest
+            }
+            None => true,
+        };
+        for ((percent, _biome), (_, i)) in out.iter().zip(nearest) {
+            observer.share(*i, *percent, keep(*percent));
+        }
+        let len_before = out.len();
+        out.retain(|(percent, _biome)| keep(*percent));
+        if out.len() != len_before {
+            // calculate new sum, and recalculate the percentages
+            let new_sum_percent: f64 = out.iter().map(|(percent, _biome)| percent).sum();
+            for (percent, _biome) in out.iter_mut() {
+                *percent /= new_sum_percent;
             }
         }
 
         out
     }
+
+    /// sample a width * depth grid of weights covering region
+    pub fn sample_region(&self, region: Region, width: usize, depth: usize) -> SampleGrid<BiomeT> {
+        let mut samples = Vec::with_capacity(width * depth);
+        for gz in 0..depth {
+            for gx in 0..width {
+                let (x, z) = grid_position(region, width, depth, gx, gz);
+                samples.push(self.get(x, z));
+            }
+        }
+        SampleGrid {
+            region,
+            width,
+            depth,
+            samples,
+        }
+    }
+
+    /// pick ONE biome for a block, the chance of a biome being picked equals its weight.
+    /// mixes biomes along borders instead of hard lines, same position + seed = same biome
+    pub fn pick_dithered(&self, x: f64, z: f64) -> Option<BiomeT> {
+        self.pick_dithered_with(x, z, DitherPattern::default())
+    }
+
+    /// same as pick_dithered() with a custom dither pattern
+    pub fn pick_dithered_with(&self, x: f64, z: f64, pattern: DitherPattern) -> Option<BiomeT> {
+        let roll = pattern.roll(
+            self.algorithm.hash_version(),
+            self.seed.wrapping_add(DITHER_SALT),
+            floor(x) as i32,
+            floor(z) as i32,
+        );
+        self.get(x, z).pick_by_weight(roll)
+    }
 }
 
-// generate a random position seeded from cell position
-#[inline(always)]
-fn cell_point(seed: u64, cell_x: i32, cell_z: i32) -> (f64, f64) {
-    let h1 = hash_u64(seed.wrapping_add(1337), cell_x, cell_z);
-    let h2 = hash_u64(seed.wrapping_add(7331), cell_x, cell_z);
+/// the steps of Worley::get_with_cells(), () ignores them
+pub(crate) trait SampleObserver<BiomeT> {
+    /// warped position in cell space and the cell containing it
+    #[inline(always)]
+    fn position(&mut self, _position: (f64, f64), _cell: (i32, i32)) {}
 
-    let fx = cell_x as f64 + ((h1 & 0xFFFF) as f64 / 65535.0);
-    let fz = cell_z as f64 + ((h2 & 0xFFFF) as f64 / 65535.0);
-    (fx, fz)
+    /// neighbor (index) of NEIGHBOR_OFFSETS
+    #[inline(always)]
+    fn candidate(
+        &mut self,
+        _index: usize,
+        _cell: (i32, i32),
+        _feature_point: (f64, f64),
+        _distance: f64,
+        _biome: BiomeT,
+    ) {
+    }
+
+    /// share of neighbor (index) among the k nearest, (kept) past kill_percent_threshold
+    #[inline(always)]
+    fn share(&mut self, _index: usize, _weight: f64, _kept: bool) {}
+}
+
+impl<BiomeT> SampleObserver<BiomeT> for () {}
+
+/// highest weight of (weights), survives any kill_percent_threshold
+#[inline(always)]
+pub(crate) fn strongest_weight<BiomeT>(weights: &[(f64, BiomeT)]) -> f64 {
+    weights
+        .iter()
+        .map(|(weight, _biome)| *weight)
+        .fold(f64::MIN, f64::max)
 }
+
+// keep dither rolls unrelated to the biome picking rolls
+const DITHER_SALT: u64 = 4242;
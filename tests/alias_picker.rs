@@ -0,0 +1,67 @@
+//! AliasBiomePicker picks biomes as often as their weights say
+
+use worley_biomes::biome_picker::BiomePicker;
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+    Plains,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest, Self::Snow, Self::Plains]
+    }
+}
+
+#[test]
+fn pick_frequencies_match_weights() {
+    const SIZE: i32 = 300;
+    let samples = (SIZE * SIZE) as f64;
+    for weights in [
+        vec![
+            (Biome::Desert, 0.4),
+            (Biome::Forest, 0.3),
+            (Biome::Snow, 0.2),
+            (Biome::Plains, 0.1),
+        ],
+        vec![(Biome::Desert, 0.05), (Biome::Forest, 0.95)],
+        vec![
+            (Biome::Snow, 0.5),
+            (Biome::Plains, 0.0),
+            (Biome::Forest, 0.5),
+        ],
+        vec![(Biome::Plains, 1.0)],
+    ] {
+        let picker = AliasBiomePicker::new(weights.clone()).expect("valid weights");
+        for hash in [HashVersion::V0, HashVersion::V1] {
+            let mut counts = [0usize; 4];
+            for x in -SIZE / 2..SIZE / 2 {
+                for z in -SIZE / 2..SIZE / 2 {
+                    counts[picker.pick_biome_versioned(17, x, z, hash) as usize] += 1;
+                }
+            }
+            for (biome, weight) in &weights {
+                let frequency = counts[*biome as usize] as f64 / samples;
+                assert!(
+                    (frequency - *weight as f64).abs() < 0.01,
+                    "{hash:?} {biome:?} picked {frequency}, weight {weight}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn default_picks_default_biome() {
+    let picker = AliasBiomePicker::<Biome>::default();
+    assert_eq!(picker.validate(), Err(WorleyError::EmptyWeights));
+    assert_eq!(
+        picker.pick_biome_versioned(3, 10, -4, HashVersion::V1),
+        Biome::Desert
+    );
+}
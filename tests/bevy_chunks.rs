@@ -0,0 +1,224 @@
+//! ChunkPlugin samples chunks around loaders in the background, closest first,
+//! despawns chunks out of range and resamples when the worley changes
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use worley_biomes::bevy::GetWorley;
+use worley_biomes::bevy::chunks::{
+    Chunk, ChunkBiomes, ChunkGenerated, ChunkLoader, ChunkMap, ChunkPlugin, ChunkSettings,
+};
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest, Self::Snow]
+    }
+}
+
+type Picker = SimpleBiomePicker<Biome>;
+
+#[derive(Resource)]
+struct Map(Worley<Biome, Picker>);
+
+impl GetWorley<Biome, Picker> for Map {
+    fn get_worley(&self) -> &Worley<Biome, Picker> {
+        &self.0
+    }
+
+    fn get_worley_mut(&mut self) -> &mut Worley<Biome, Picker> {
+        &mut self.0
+    }
+}
+
+const SETTINGS: ChunkSettings = ChunkSettings {
+    chunk_size: 16.0,
+    resolution: 8,
+    load_distance: 1,
+    unload_distance: 2,
+    max_tasks: 1,
+};
+
+// sampling happens on other threads
+fn update_until(app: &mut App, mut done: impl FnMut(&mut App) -> bool) {
+    let start = Instant::now();
+    while !done(app) {
+        assert!(start.elapsed() < Duration::from_secs(10), "timed out");
+        app.update();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn generated(app: &mut App) -> Vec<IVec2> {
+    app.world_mut()
+        .resource_mut::<Messages<ChunkGenerated>>()
+        .drain()
+        .map(|generated| generated.chunk)
+        .collect()
+}
+
+fn all_generated(app: &mut App) -> bool {
+    let world = app.world_mut();
+    let mut query = world.query::<(&Chunk, Option<&ChunkBiomes<Biome>>)>();
+    query.iter(world).count() == 9 && query.iter(world).all(|(_, biomes)| biomes.is_some())
+}
+
+#[test]
+fn chunks_follow_loader() {
+    let worley: Worley<Biome, Picker> = Worley::builder().seed(5).zoom(10.0).build().unwrap();
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        ChunkPlugin::<Map, Biome, Picker> {
+            settings: SETTINGS,
+            ..default()
+        },
+    ))
+    .insert_resource(Map(worley));
+    let loader = app
+        .world_mut()
+        .spawn((ChunkLoader, Transform::from_xyz(20.0, 0.0, -4.0)))
+        .id();
+
+    let mut order = Vec::new();
+    update_until(&mut app, |app| {
+        order.extend(generated(app));
+        all_generated(app)
+    });
+    order.extend(generated(&mut app));
+    // one task at a time: the loader's own chunk first, then by distance to the loader
+    assert_eq!(order.len(), 9);
+    assert_eq!(order[0], IVec2::new(1, -1));
+    let distance = |chunk: &IVec2| {
+        let center = (chunk.as_vec2() + 0.5) * SETTINGS.chunk_size as f32;
+        center.distance(Vec2::new(20.0, -4.0))
+    };
+    assert!(order.windows(2).all(|w| distance(&w[0]) <= distance(&w[1])));
+
+    // samples match Worley::sample_region()
+    let world = app.world_mut();
+    let mut query = world.query::<(&Chunk, &ChunkBiomes<Biome>)>();
+    for (chunk, biomes) in query.iter(world) {
+        let region = Region::chunk(chunk.0.x, chunk.0.y, SETTINGS.chunk_size);
+        let expected = world.resource::<Map>().0.sample_region(region, 8, 8);
+        assert_eq!(biomes.0.samples, expected.samples);
+        assert_eq!(biomes.0.region, region);
+    }
+
+    // moving away despawns the old chunks
+    app.world_mut()
+        .get_mut::<Transform>(loader)
+        .unwrap()
+        .translation = Vec3::new(200.0, 0.0, 200.0);
+    app.update();
+    update_until(&mut app, all_generated);
+    let chunk_map = app.world().resource::<ChunkMap>();
+    assert_eq!(chunk_map.len(), 9);
+    assert!(chunk_map.get(IVec2::new(1, -1)).is_none());
+    assert!(chunk_map.get(IVec2::new(12, 12)).is_some());
+
+    // a changed worley resamples every chunk
+    generated(&mut app);
+    app.world_mut().resource_mut::<Map>().0.seed = 6;
+    let mut resampled = Vec::new();
+    update_until(&mut app, |app| {
+        resampled.extend(generated(app));
+        resampled.len() == 9
+    });
+    let world = app.world_mut();
+    let mut query = world.query::<(&Chunk, &ChunkBiomes<Biome>)>();
+    for (chunk, biomes) in query.iter(world) {
+        let region = Region::chunk(chunk.0.x, chunk.0.y, SETTINGS.chunk_size);
+        let expected = world.resource::<Map>().0.sample_region(region, 8, 8);
+        assert_eq!(biomes.0.samples, expected.samples);
+    }
+}
+
+#[test]
+fn invalid_settings() {
+    let cases = [
+        (0.0, 1, 2),
+        (-16.0, 1, 2),
+        (f64::NAN, 1, 2),
+        (f64::INFINITY, 1, 2),
+    ];
+    for (chunk_size, load_distance, unload_distance) in cases {
+        let settings = ChunkSettings {
+            chunk_size,
+            load_distance,
+            unload_distance,
+            ..SETTINGS
+        };
+        assert!(matches!(
+            settings.validate(),
+            Err(WorleyError::InvalidChunkSize(_))
+        ));
+    }
+    for (load, unload) in [(2, 1), (-1, 2)] {
+        let settings = ChunkSettings {
+            load_distance: load,
+            unload_distance: unload,
+            ..SETTINGS
+        };
+        assert_eq!(
+            settings.validate(),
+            Err(WorleyError::InvalidChunkDistance { load, unload })
+        );
+    }
+    assert_eq!(SETTINGS.validate(), Ok(()));
+    assert_eq!(ChunkSettings::default().validate(), Ok(()));
+}
+
+#[test]
+#[should_panic(expected = "invalid ChunkPlugin settings")]
+fn plugin_rejects_invalid_settings() {
+    App::new().add_plugins(ChunkPlugin::<Map, Biome, Picker> {
+        settings: ChunkSettings {
+            chunk_size: 0.0,
+            ..SETTINGS
+        },
+        ..default()
+    });
+}
+
+#[test]
+fn invalid_runtime_settings_pause_chunks() {
+    let worley: Worley<Biome, Picker> = Worley::builder().seed(5).zoom(10.0).build().unwrap();
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        ChunkPlugin::<Map, Biome, Picker> {
+            settings: SETTINGS,
+            ..default()
+        },
+    ))
+    .insert_resource(Map(worley));
+    // far out chunk positions saturate instead of overflowing,
+    // the chunks past the end of i32 collapse onto the last one
+    app.world_mut()
+        .spawn((ChunkLoader, Transform::from_xyz(1e12, 0.0, -1e12)));
+    app.update();
+    assert_eq!(app.world().resource::<ChunkMap>().len(), 4);
+    assert!(
+        app.world()
+            .resource::<ChunkMap>()
+            .get(IVec2::new(i32::MAX, i32::MIN))
+            .is_some()
+    );
+
+    app.world_mut().resource_mut::<ChunkSettings>().chunk_size = -1.0;
+    for _ in 0..5 {
+        app.update();
+    }
+    // nothing is loaded or unloaded with a negative chunk size
+    assert_eq!(app.world().resource::<ChunkMap>().len(), 4);
+}
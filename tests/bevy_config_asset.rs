@@ -0,0 +1,137 @@
+//! WorleyConfigPlugin loads .worley.ron assets into the GetWorley resource,
+//! re-applies them when the asset changes and reports broken files
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use worley_biomes::bevy::GetWorley;
+use worley_biomes::bevy::config_asset::{
+    ActiveWorleyConfig, WorleyConfig, WorleyConfigError, WorleyConfigPlugin,
+};
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest]
+    }
+}
+
+type Picker = SimpleBiomePicker<Biome>;
+
+#[derive(Resource, Default)]
+struct Map(Worley<Biome, Picker>);
+
+impl GetWorley<Biome, Picker> for Map {
+    fn get_worley(&self) -> &Worley<Biome, Picker> {
+        &self.0
+    }
+
+    fn get_worley_mut(&mut self) -> &mut Worley<Biome, Picker> {
+        &mut self.0
+    }
+}
+
+fn app(assets: &Path) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin {
+            file_path: assets.to_string_lossy().into_owned(),
+            ..default()
+        },
+        WorleyConfigPlugin::<Map, Biome, Picker>::new("test.worley.ron"),
+    ))
+    .init_resource::<Map>();
+    app
+}
+
+// loading happens on other threads
+fn update_until(app: &mut App, done: impl Fn(&mut App) -> bool) {
+    let start = Instant::now();
+    while !done(app) {
+        assert!(start.elapsed() < Duration::from_secs(10), "timed out");
+        app.update();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn config_asset_drives_resource() {
+    let assets = Path::new(env!("CARGO_TARGET_TMPDIR")).join("config_asset");
+    std::fs::create_dir_all(&assets).unwrap();
+    let worley: Worley<Biome, Picker> = Worley::builder().seed(42).zoom(64.0).build().unwrap();
+    std::fs::write(assets.join("test.worley.ron"), worley.to_ron().unwrap()).unwrap();
+    std::fs::write(assets.join("broken.worley.ron"), "(version: 1, zoom: -1.0)").unwrap();
+    std::fs::write(assets.join("binary.worley.ron"), [0xff, 0xfe, 0x00]).unwrap();
+
+    let mut app = app(&assets);
+    update_until(&mut app, |app| app.world().resource::<Map>().0.seed == 42);
+    assert_eq!(app.world().resource::<Map>().0.zoom, 64.0);
+
+    // changing the asset (what a hot reload does) updates the resource
+    let handle = app
+        .world()
+        .resource::<ActiveWorleyConfig<Biome, Picker>>()
+        .0
+        .clone();
+    app.world_mut()
+        .resource_mut::<Assets<WorleyConfig<Biome, Picker>>>()
+        .get_mut(&handle)
+        .unwrap()
+        .worley
+        .seed = 7;
+    update_until(&mut app, |app| app.world().resource::<Map>().0.seed == 7);
+
+    // broken files are reported and leave the resource alone
+    let broken = app
+        .world()
+        .resource::<AssetServer>()
+        .load("broken.worley.ron");
+    app.insert_resource(ActiveWorleyConfig::<Biome, Picker>(broken));
+    update_until(&mut app, |app| {
+        !app.world()
+            .resource::<Messages<WorleyConfigError>>()
+            .is_empty()
+    });
+    let error = app
+        .world_mut()
+        .resource_mut::<Messages<WorleyConfigError>>()
+        .drain()
+        .next()
+        .unwrap();
+    assert_eq!(error.path.unwrap().to_string(), "broken.worley.ron");
+    assert!(error.error.contains("zoom"), "{}", error.error);
+    assert_eq!(app.world().resource::<Map>().0.seed, 7);
+
+    // unreadable files are reported as read errors, not as broken RON
+    let binary = app
+        .world()
+        .resource::<AssetServer>()
+        .load("binary.worley.ron");
+    app.insert_resource(ActiveWorleyConfig::<Biome, Picker>(binary));
+    update_until(&mut app, |app| {
+        !app.world()
+            .resource::<Messages<WorleyConfigError>>()
+            .is_empty()
+    });
+    let error = app
+        .world_mut()
+        .resource_mut::<Messages<WorleyConfigError>>()
+        .drain()
+        .next()
+        .unwrap();
+    assert!(
+        error.error.contains("failed to read worley config"),
+        "{}",
+        error.error
+    );
+    assert_eq!(app.world().resource::<Map>().0.seed, 7);
+}
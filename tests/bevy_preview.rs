@@ -0,0 +1,202 @@
+//! the DebugPlugin preview pans and zooms around the cursor, labels the biomes under it
+//! and shows the passes of the latest rebuild only
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use worley_biomes::bevy::debug_plugin::{
+    COARSE_STEP, DebugColor, DebugPlugin, DebugPluginSettings, GetWorley, PreviewView, WorleyImage,
+    sample_preview,
+};
+use worley_biomes::prelude::*;
+use worley_biomes::registry::BiomeId;
+
+fn image() -> WorleyImage {
+    let mut image = WorleyImage::new(Handle::default(), 500.0);
+    image.preview_offset = (-120.0, 35.5);
+    image.preview_zoom = 2.0;
+    image
+}
+
+#[test]
+fn world_position_spans_the_image() {
+    let image = image();
+    assert_eq!(image.world_size(), 1000.0);
+    assert_eq!(image.world_position(Vec2::ZERO), (-120.0, 35.5));
+    // rows go along x, columns along z
+    assert_eq!(image.world_position(Vec2::new(1.0, 0.0)), (-120.0, 1035.5));
+    assert_eq!(image.world_position(Vec2::new(0.0, 1.0)), (880.0, 35.5));
+}
+
+#[test]
+fn zoom_keeps_the_cursor_in_place() {
+    for uv in [Vec2::ZERO, Vec2::new(0.25, 0.75), Vec2::ONE] {
+        for factor in [0.5, 1.1, 3.0, 1000.0] {
+            let mut image = image();
+            let (x, z) = image.world_position(uv);
+            image.zoom_at(uv, factor);
+            let (zoomed_x, zoomed_z) = image.world_position(uv);
+            assert!((zoomed_x - x).abs() < 1e-9, "{uv} {factor}");
+            assert!((zoomed_z - z).abs() < 1e-9, "{uv} {factor}");
+        }
+    }
+    // clamped to 0.01 -> 100.0
+    let mut image = image();
+    image.zoom_at(Vec2::splat(0.5), 1000.0);
+    assert_eq!(image.preview_zoom, 100.0);
+}
+
+#[test]
+fn pan_follows_the_cursor() {
+    let mut image = image();
+    let start = Vec2::new(0.25, 0.5);
+    let (x, z) = image.world_position(start);
+    let moved = Vec2::new(0.5, 0.375);
+    image.pan(moved - start);
+    // the world point that was under the cursor moved with it
+    let (panned_x, panned_z) = image.world_position(moved);
+    assert!((panned_x - x).abs() < 1e-9);
+    assert!((panned_z - z).abs() < 1e-9);
+
+    image.reset();
+    assert_eq!(image.preview_offset, (0.0, 0.0));
+    assert_eq!(image.preview_zoom, 1.0);
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest]
+    }
+}
+
+impl DebugColor<Biome> for Biome {
+    fn get_color(&self) -> Srgba {
+        match self {
+            Biome::Desert => Srgba::rgb(0.9, 0.8, 0.3),
+            Biome::Forest => Srgba::rgb(0.1, 0.6, 0.2),
+        }
+    }
+}
+
+#[test]
+fn debug_name_defaults_to_debug() {
+    assert_eq!(Biome::Forest.debug_name(), "Forest");
+    assert_eq!(Biome::Desert.debug_name(), "Desert");
+    assert_eq!(BiomeId(7).debug_name(), "#7");
+}
+
+type Picker = SimpleBiomePicker<Biome>;
+
+#[derive(Resource)]
+struct Map(Worley<Biome, Picker>);
+
+impl GetWorley<Biome, Picker> for Map {
+    fn get_worley(&self) -> &Worley<Biome, Picker> {
+        &self.0
+    }
+
+    fn get_worley_mut(&mut self) -> &mut Worley<Biome, Picker> {
+        &mut self.0
+    }
+}
+
+// hold the passes of a worley until the test opens its gate
+static STALE_GATE: AtomicBool = AtomicBool::new(false);
+static LATEST_GATE: AtomicBool = AtomicBool::new(false);
+
+fn wait_for(gate: &AtomicBool) -> f32 {
+    while !gate.load(Ordering::Acquire) {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    0.0
+}
+
+fn gated(seed: u64, gate: &'static AtomicBool) -> Worley<Biome, Picker> {
+    Worley::builder()
+        .seed(seed)
+        .zoom(10.0)
+        .warp_strength(1.0)
+        .warp_noise(Noise::custom(NoiseFn(move |_x: f32, _z: f32| {
+            wait_for(gate)
+        })))
+        .build()
+        .unwrap()
+}
+
+fn image_data(app: &App) -> Option<(u32, Vec<u8>)> {
+    let world = app.world();
+    let handle = world.resource::<WorleyImage>().handle();
+    let image = world.resource::<Assets<Image>>().get(handle)?;
+    Some((image.width(), image.data.clone()?))
+}
+
+#[test]
+fn rebuild_drops_stale_passes() {
+    let resolution = 32;
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        InputPlugin,
+        DebugPlugin::<Map, Biome, Picker> {
+            settings: DebugPluginSettings {
+                spawn_preview_image: false,
+                preview_resolution: resolution,
+                ..default()
+            },
+            _phantom: PhantomData,
+        },
+    ))
+    .init_asset::<Image>()
+    .insert_resource(Map(gated(1, &STALE_GATE)));
+    // spawns the preview, then starts the stale passes
+    app.update();
+    app.update();
+
+    // a rebuild mid computation, then the stale passes finish first
+    let latest = gated(2, &LATEST_GATE);
+    app.world_mut().resource_mut::<Map>().0 = latest.clone();
+    app.update();
+    STALE_GATE.store(true, Ordering::Release);
+    for _ in 0..50 {
+        std::thread::sleep(Duration::from_millis(1));
+        app.update();
+        let (_, data) = image_data(&app).unwrap();
+        assert!(data.iter().all(|byte| *byte == 0), "stale pass shown");
+    }
+
+    LATEST_GATE.store(true, Ordering::Release);
+    let view = PreviewView::new(app.world().resource::<WorleyImage>(), resolution);
+    let coarse = sample_preview(&latest, view, COARSE_STEP);
+    let full = sample_preview(&latest, view, 1);
+    assert_eq!(coarse.size, resolution / COARSE_STEP);
+    assert_eq!(full.size, resolution);
+
+    let start = Instant::now();
+    loop {
+        assert!(start.elapsed() < Duration::from_secs(10), "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+        app.update();
+        let (size, data) = image_data(&app).unwrap();
+        // still empty, or the coarse pass scaled up until the full one is in
+        if data.iter().all(|byte| *byte == 0) {
+            continue;
+        }
+        if size == coarse.size {
+            assert_eq!(data, coarse.data);
+            continue;
+        }
+        assert_eq!((size, data), (full.size, full.data));
+        break;
+    }
+}
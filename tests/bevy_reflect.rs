@@ -0,0 +1,161 @@
+//! WorleyReflectPlugin registers Worley for inspectors and scenes,
+//! reflected edits and scenes keep sampling like the builder would
+use bevy::prelude::*;
+use bevy::reflect::{GetPath, TypeRegistry};
+use bevy::scene::{DynamicScene, DynamicSceneBuilder, serde::SceneDeserializer};
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Serialize};
+use worley_biomes::bevy::GetWorley;
+use worley_biomes::bevy::reflect_plugin::WorleyReflectPlugin;
+use worley_biomes::distance_fn::DistanceFn;
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Reflect, Serialize, Deserialize)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest, Self::Snow]
+    }
+}
+
+type Picker = SimpleBiomePicker<Biome>;
+
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+struct Map(Worley<Biome, Picker>);
+
+impl GetWorley<Biome, Picker> for Map {
+    fn get_worley(&self) -> &Worley<Biome, Picker> {
+        &self.0
+    }
+
+    fn get_worley_mut(&mut self) -> &mut Worley<Biome, Picker> {
+        &mut self.0
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        bevy::scene::ScenePlugin,
+        WorleyReflectPlugin::<Map, Biome, Picker>::default(),
+    ));
+    app
+}
+
+fn built() -> Worley<Biome, Picker> {
+    Worley::builder()
+        .seed(11)
+        .zoom(30.0)
+        .distance_fn(DistanceFn::Manhattan)
+        .warp_strength(2.0)
+        .noise_seed(3)
+        .biome_picker(SimpleBiomePicker::AnyOf(vec![Biome::Forest, Biome::Snow]))
+        .build()
+        .unwrap()
+}
+
+fn assert_same(a: &Worley<Biome, Picker>, b: &Worley<Biome, Picker>) {
+    for i in 0..64 {
+        let (x, z) = (i as f64 * 7.3, i as f64 * -4.1);
+        assert_eq!(a.get(x, z), b.get(x, z));
+    }
+}
+
+#[test]
+fn reflected_edits_sample_like_the_builder() {
+    let mut app = app();
+    let registry = app.world().resource::<AppTypeRegistry>().clone();
+    for type_path in [
+        "worley_biomes::noise::Noise",
+        "worley_biomes::warp::WarpSettings",
+        "worley_biomes::distance_fn::DistanceFn",
+    ] {
+        assert!(registry.read().get_with_type_path(type_path).is_some());
+    }
+    assert!(
+        registry
+            .read()
+            .get(std::any::TypeId::of::<Worley<Biome, Picker>>())
+            .is_some()
+    );
+
+    app.insert_resource(Map(Worley::builder().seed(11).zoom(30.0).build().unwrap()));
+    app.update();
+    {
+        let mut map = app.world_mut().resource_mut::<Map>();
+        *map.path_mut::<DistanceFn>(".0.distance_fn_config").unwrap() = DistanceFn::Manhattan;
+        *map.path_mut::<f32>(".0.warp_settings.strength").unwrap() = 2.0;
+        map.0.warp_settings.noise.set_seed(3);
+        map.0.biome_picker = SimpleBiomePicker::AnyOf(vec![Biome::Forest, Biome::Snow]);
+    }
+    app.update();
+    assert_same(&app.world().resource::<Map>().0, &built());
+}
+
+#[test]
+fn worley_survives_a_scene() {
+    let mut app = app();
+    app.insert_resource(Map(built()));
+    let registry = app.world().resource::<AppTypeRegistry>().clone();
+    let scene = DynamicSceneBuilder::from_world(app.world())
+        .allow_resource::<Map>()
+        .extract_resources()
+        .build();
+    let ron = scene.serialize(&registry.read()).unwrap();
+    assert!(ron.contains("distance_fn_config"), "{ron}");
+
+    let loaded = deserialize(&ron, &registry.read());
+    let mut other = self::app();
+    loaded
+        .write_to_world(other.world_mut(), &mut Default::default())
+        .unwrap();
+    // from_reflect() already rebuilt distance_fn, no frame has to run first
+    let map = other.world().resource::<Map>();
+    assert_eq!(map.0.distance_fn_config, DistanceFn::Manhattan);
+    assert_same(&map.0, &built());
+}
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct Terrain(Worley<Biome, Picker>);
+
+#[test]
+fn worley_component_survives_a_scene() {
+    let mut app = app();
+    app.register_type::<Terrain>();
+    let entity = app.world_mut().spawn(Terrain(built())).id();
+    let registry = app.world().resource::<AppTypeRegistry>().clone();
+    let scene = DynamicSceneBuilder::from_world(app.world())
+        .extract_entity(entity)
+        .build();
+    let ron = scene.serialize(&registry.read()).unwrap();
+
+    let loaded = deserialize(&ron, &registry.read());
+    let mut other = self::app();
+    other.register_type::<Terrain>();
+    loaded
+        .write_to_world(other.world_mut(), &mut Default::default())
+        .unwrap();
+    let world = other.world_mut();
+    let terrain = world.query::<&Terrain>().single(world).unwrap();
+    assert_eq!(terrain.0.distance_fn_config, DistanceFn::Manhattan);
+    assert_same(&terrain.0, &built());
+}
+
+fn deserialize(ron: &str, registry: &TypeRegistry) -> DynamicScene {
+    let mut deserializer = ron::de::Deserializer::from_str(ron).unwrap();
+    SceneDeserializer {
+        type_registry: registry,
+    }
+    .deserialize(&mut deserializer)
+    .unwrap()
+}
@@ -0,0 +1,111 @@
+//! WorleyBiomesPlugin keeps BiomeInfluence in sync with the entity position,
+//! sends enter/exit messages and BiomeSampler agrees with Worley
+use bevy::prelude::*;
+use worley_biomes::bevy::GetWorley;
+use worley_biomes::bevy::runtime_plugin::{
+    BiomeEntered, BiomeExited, BiomeInfluence, BiomeSampler, WorleyBiomesPlugin,
+};
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest, Self::Snow]
+    }
+}
+
+type Picker = SimpleBiomePicker<Biome>;
+
+#[derive(Resource)]
+struct Map(Worley<Biome, Picker>);
+
+impl GetWorley<Biome, Picker> for Map {
+    fn get_worley(&self) -> &Worley<Biome, Picker> {
+        &self.0
+    }
+
+    fn get_worley_mut(&mut self) -> &mut Worley<Biome, Picker> {
+        &mut self.0
+    }
+}
+
+#[derive(Resource, Default)]
+struct Probe(Vec3, Option<Biome>);
+
+fn probe(sampler: BiomeSampler<Map, Biome, Picker>, mut probe: ResMut<Probe>) {
+    probe.1 = sampler.dominant(probe.0);
+}
+
+fn drain<M: Message + Clone>(app: &mut App) -> Vec<M> {
+    app.world_mut()
+        .resource_mut::<Messages<M>>()
+        .drain()
+        .collect()
+}
+
+#[test]
+fn influence_follows_entity() {
+    let worley: Worley<Biome, Picker> = Worley::builder().seed(3).zoom(20.0).build().unwrap();
+    let dominant = |x: f32| worley.get(x as f64, 0.0).dominant().unwrap();
+    // two spots on the x axis with different biomes
+    let start = Vec3::new(0.0, 5.0, 0.0);
+    let end_x = (1..1000)
+        .map(|x| x as f32)
+        .find(|x| dominant(*x) != dominant(0.0))
+        .expect("a second biome");
+    let end = Vec3::new(end_x, 5.0, 0.0);
+    let (start_biome, end_biome) = (dominant(0.0), dominant(end_x));
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        WorleyBiomesPlugin::<Map, Biome, Picker>::default(),
+    ))
+    .insert_resource(Map(worley))
+    .insert_resource(Probe(end, None))
+    .add_systems(Update, probe);
+
+    let entity = app
+        .world_mut()
+        .spawn((
+            Transform::from_translation(start),
+            BiomeInfluence::<Biome>::default(),
+        ))
+        .id();
+    app.update();
+    let influence = app.world().get::<BiomeInfluence<Biome>>(entity).unwrap();
+    assert_eq!(influence.dominant, Some(start_biome));
+    assert_eq!(
+        influence.weights.as_slice(),
+        app.world().resource::<Map>().0.get(0.0, 0.0).as_slice()
+    );
+    let entered = drain::<BiomeEntered<Biome>>(&mut app);
+    assert_eq!(entered.len(), 1);
+    assert_eq!((entered[0].entity, entered[0].biome), (entity, start_biome));
+    assert!(drain::<BiomeExited<Biome>>(&mut app).is_empty());
+    assert_eq!(app.world().resource::<Probe>().1, Some(end_biome));
+
+    // standing still: nothing new
+    app.update();
+    assert!(drain::<BiomeEntered<Biome>>(&mut app).is_empty());
+
+    app.world_mut()
+        .get_mut::<Transform>(entity)
+        .unwrap()
+        .translation = end;
+    app.update();
+    let influence = app.world().get::<BiomeInfluence<Biome>>(entity).unwrap();
+    assert_eq!(influence.dominant, Some(end_biome));
+    let exited = drain::<BiomeExited<Biome>>(&mut app);
+    let entered = drain::<BiomeEntered<Biome>>(&mut app);
+    assert_eq!((exited.len(), exited[0].biome), (1, start_biome));
+    assert_eq!((entered.len(), entered[0].biome), (1, end_biome));
+}
@@ -0,0 +1,68 @@
+//! BiomeBlend edge cases: roll bounds, ties and empty weights
+
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Biome {
+    Desert,
+    Forest,
+    Snow,
+}
+
+use Biome::*;
+
+#[test]
+fn pick_by_weight_roll_bounds() {
+    let weights = [(0.25, Desert), (0.5, Forest), (0.25, Snow)];
+    assert_eq!(weights.pick_by_weight(0.0), Some(Desert));
+    assert_eq!(weights.pick_by_weight(0.25), Some(Forest));
+    assert_eq!(weights.pick_by_weight(0.999), Some(Snow));
+    assert_eq!(weights.pick_by_weight(1.0), Some(Snow));
+
+    // zero weights can't be picked at either end
+    let weights = [(0.0, Desert), (1.0, Forest), (0.0, Snow)];
+    assert_eq!(weights.pick_by_weight(0.0), Some(Forest));
+    assert_eq!(weights.pick_by_weight(1.0), Some(Forest));
+
+    // unnormalized weights are scaled by their sum
+    let weights = [(2.0, Desert), (6.0, Forest)];
+    assert_eq!(weights.pick_by_weight(0.24), Some(Desert));
+    assert_eq!(weights.pick_by_weight(0.26), Some(Forest));
+}
+
+#[test]
+fn pick_by_weight_all_zero() {
+    let weights = [(0.0, Desert), (0.0, Forest)];
+    assert_eq!(weights.pick_by_weight(0.0), Some(Forest));
+    assert_eq!(weights.pick_by_weight(1.0), Some(Forest));
+}
+
+#[test]
+fn dominant_ties_pick_the_first() {
+    let weights = [(0.2, Desert), (0.4, Forest), (0.4, Snow)];
+    assert_eq!(weights.dominant(), Some(Forest));
+    let weights = [(0.5, Snow), (0.5, Desert)];
+    assert_eq!(weights.dominant(), Some(Snow));
+    assert_eq!([(1.0, Desert)].dominant(), Some(Desert));
+}
+
+#[test]
+fn empty_weights() {
+    let weights: [(f64, Biome); 0] = [];
+    assert_eq!(weights.pick_by_weight(0.0), None);
+    assert_eq!(weights.pick_by_weight(1.0), None);
+    assert_eq!(weights.dominant(), None);
+    assert_eq!(weights.blend(|_| 1.0f32), None);
+}
+
+#[test]
+fn blend_matches_weighted_average() {
+    let weights = [(0.25, Desert), (0.5, Forest), (0.25, Snow)];
+    let height = |biome: &Biome| match biome {
+        Desert => 10.0,
+        Forest => 20.0,
+        Snow => 60.0,
+    };
+    let blended: f64 = weights.blend(height).unwrap();
+    assert!((blended - 27.5).abs() < 1e-9, "{blended}");
+}
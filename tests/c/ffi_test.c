@@ -0,0 +1,124 @@
+// calls the C API the way an engine would, driven by tests/ffi_c.rs:
+// ffi_test <config.worley.ron> <biome names...>
+// prints every result, weights as f64 bits so the rust side can compare exactly
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "worley_biomes.h"
+
+static char *read_file(const char *path) {
+    FILE *file = fopen(path, "rb");
+    if (!file) {
+        return NULL;
+    }
+    fseek(file, 0, SEEK_END);
+    long len = ftell(file);
+    fseek(file, 0, SEEK_SET);
+    char *text = malloc((size_t)len + 1);
+    if (fread(text, 1, (size_t)len, file) != (size_t)len) {
+        fclose(file);
+        free(text);
+        return NULL;
+    }
+    text[len] = '\0';
+    fclose(file);
+    return text;
+}
+
+static unsigned long long bits(double value) {
+    unsigned long long out;
+    memcpy(&out, &value, sizeof(out));
+    return out;
+}
+
+static void print_weight(WbBiomeWeight weight) {
+    printf(" %016llx:%u", bits(weight.weight), (unsigned)weight.biome);
+}
+
+int main(int argc, char **argv) {
+    if (argc < 3) {
+        fprintf(stderr, "usage: ffi_test <config.worley.ron> <biome names...>\n");
+        return 2;
+    }
+    char *config = read_file(argv[1]);
+    if (!config) {
+        fprintf(stderr, "can't read %s\n", argv[1]);
+        return 2;
+    }
+    const char *const *names = (const char *const *)&argv[2];
+    size_t name_count = (size_t)(argc - 2);
+
+    char error[256] = {0};
+    WbWorley *broken = wb_worley_from_ron(names, name_count, "(not a config", error, sizeof(error));
+    printf("broken %s %s\n", broken ? "created" : "null", error[0] ? "error" : "silent");
+
+    WbWorley *worley = wb_worley_from_ron(names, name_count, config, error, sizeof(error));
+    free(config);
+    if (!worley) {
+        fprintf(stderr, "wb_worley_from_ron: %s\n", error);
+        return 1;
+    }
+
+    // the biome list is shared by every worley, a different one is refused
+    const char *other[] = {"Nowhere"};
+    WbWorley *mismatch = wb_worley_from_ron(other, 1, "", error, sizeof(error));
+    printf("mismatch %s\n", mismatch ? "created" : "null");
+
+    size_t count = wb_biome_count(worley);
+    printf("biomes %zu\n", count);
+    for (size_t i = 0; i < count; i++) {
+        char name[64];
+        size_t len = wb_biome_name(worley, (uint16_t)i, name, sizeof(name));
+        printf("biome %zu %s %zu %u\n", i, name, len, (unsigned)wb_biome_id(worley, name));
+    }
+    char short_name[3];
+    size_t full_len = wb_biome_name(worley, 0, short_name, sizeof(short_name));
+    printf("truncated %s %zu\n", short_name, full_len);
+    printf("unknown %u\n", (unsigned)wb_biome_id(worley, "Nowhere"));
+
+    const double points[][2] = {{0.0, 0.0}, {12.5, -40.25}, {-300.75, 88.0}, {1000.0, 1000.0}};
+    for (size_t i = 0; i < sizeof(points) / sizeof(points[0]); i++) {
+        double x = points[i][0], z = points[i][1];
+        WbBiomeWeight weights[WB_MAX_K];
+        size_t n = wb_worley_sample(worley, x, z, weights, WB_MAX_K);
+        printf("sample %zu", i);
+        for (size_t w = 0; w < n; w++) {
+            print_weight(weights[w]);
+        }
+        printf("\n");
+        printf("dominant %zu %u\n", i, (unsigned)wb_worley_dominant(worley, x, z));
+        printf("dithered %zu %u\n", i, (unsigned)wb_worley_pick_dithered(worley, x, z));
+    }
+
+    enum { WIDTH = 4, DEPTH = 3, STRIDE = 4 };
+    WbBiomeWeight region[WIDTH * DEPTH * STRIDE];
+    printf("region too small %d\n",
+           wb_worley_sample_region(worley, -64.0, -32.0, 64.0, 32.0, WIDTH, DEPTH, STRIDE, region, 1));
+    printf("region zero stride %d\n",
+           wb_worley_sample_region(worley, -64.0, -32.0, 64.0, 32.0, WIDTH, DEPTH, 0, region,
+                                   WIDTH * DEPTH * STRIDE));
+    printf("region %d\n",
+           wb_worley_sample_region(worley, -64.0, -32.0, 64.0, 32.0, WIDTH, DEPTH, STRIDE, region,
+                                   WIDTH * DEPTH * STRIDE));
+    for (size_t i = 0; i < WIDTH * DEPTH; i++) {
+        printf("cell %zu", i);
+        for (size_t w = 0; w < STRIDE; w++) {
+            print_weight(region[i * STRIDE + w]);
+        }
+        printf("\n");
+    }
+
+    uint16_t dominant[WIDTH * DEPTH];
+    printf("dominant region %d",
+           wb_worley_dominant_region(worley, -64.0, -32.0, 64.0, 32.0, WIDTH, DEPTH, dominant,
+                                     WIDTH * DEPTH));
+    for (size_t i = 0; i < WIDTH * DEPTH; i++) {
+        printf(" %u", (unsigned)dominant[i]);
+    }
+    printf("\n");
+
+    wb_worley_free(worley);
+    wb_worley_free(NULL);
+    return 0;
+}
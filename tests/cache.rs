@@ -0,0 +1,176 @@
+//! CachedWorley returns what Worley returns, and evicts the least recently used entries
+
+use std::sync::Arc;
+
+use worley_biomes::cache::CacheStats;
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest, Self::Snow]
+    }
+}
+
+fn worley() -> Worley<Biome, SimpleBiomePicker<Biome>> {
+    Worley::builder()
+        .seed(17)
+        .zoom(12.0)
+        .sharpness(3.0)
+        .warp_strength(0.8)
+        .build()
+        .expect("valid worley")
+}
+
+fn cached(
+    cell_capacity: usize,
+    chunk_capacity: usize,
+) -> CachedWorley<Biome, SimpleBiomePicker<Biome>> {
+    CachedWorley::new(
+        worley(),
+        CacheSettings {
+            cell_capacity,
+            chunk_capacity,
+            chunk_size: 16.0,
+            chunk_resolution: 4,
+        },
+    )
+}
+
+#[test]
+fn get_matches_worley() {
+    let worley = worley();
+    // tiny capacities evict all the time, results must not change
+    for cell_capacity in [0, 1, 5, 4096] {
+        let cached = cached(cell_capacity, 0);
+        for i in 0..2000 {
+            let (x, z) = ((i % 40) as f64 * 2.3 - 40.0, (i / 40) as f64 * 1.7 - 40.0);
+            assert_eq!(
+                cached.get(x, z),
+                worley.get(x, z),
+                "capacity {cell_capacity}"
+            );
+        }
+    }
+}
+
+#[test]
+fn chunk_matches_sample_region() {
+    let worley = worley();
+    let cached = cached(64, 4);
+    for (chunk_x, chunk_z) in [(0, 0), (-3, 2), (0, 0)] {
+        let region = Region::chunk(chunk_x, chunk_z, 16.0);
+        assert_eq!(
+            cached.chunk(chunk_x, chunk_z).samples,
+            worley.sample_region(region, 4, 4).samples
+        );
+    }
+}
+
+#[test]
+fn least_recently_used_is_evicted() {
+    let cached = cached(0, 2);
+    let a = cached.chunk(0, 0);
+    cached.chunk(1, 0);
+    // touching (0, 0) makes (1, 0) the least recently used
+    assert!(Arc::ptr_eq(&a, &cached.chunk(0, 0)));
+    cached.chunk(2, 0);
+    assert_eq!(
+        cached.stats(),
+        CacheStats {
+            chunk_hits: 1,
+            chunk_misses: 3,
+            evictions: 1,
+            ..CacheStats::default()
+        }
+    );
+
+    // (0, 0) survived, (1, 0) was evicted
+    assert!(Arc::ptr_eq(&a, &cached.chunk(0, 0)));
+    cached.chunk(1, 0);
+    let stats = cached.stats();
+    assert_eq!((stats.chunk_hits, stats.chunk_misses), (2, 4));
+    // and evicted (2, 0), the least recently used after touching (0, 0)
+    assert_eq!(stats.evictions, 2);
+    cached.chunk(0, 0);
+    cached.chunk(2, 0);
+    let stats = cached.stats();
+    assert_eq!((stats.chunk_hits, stats.chunk_misses), (3, 5));
+}
+
+#[test]
+fn capacity_one_keeps_the_last_entry() {
+    let cached = cached(0, 1);
+    cached.chunk(0, 0);
+    cached.chunk(0, 0);
+    cached.chunk(5, 5);
+    cached.chunk(5, 5);
+    cached.chunk(0, 0);
+    assert_eq!(
+        cached.stats(),
+        CacheStats {
+            chunk_hits: 2,
+            chunk_misses: 3,
+            evictions: 2,
+            ..CacheStats::default()
+        }
+    );
+}
+
+#[test]
+fn capacity_zero_disables_caching() {
+    let cached = cached(0, 0);
+    let a = cached.chunk(0, 0);
+    let b = cached.chunk(0, 0);
+    assert!(!Arc::ptr_eq(&a, &b));
+    assert_eq!(a.samples, b.samples);
+    cached.get(1.0, 2.0);
+    assert_eq!(
+        cached.stats(),
+        CacheStats {
+            chunk_misses: 2,
+            ..CacheStats::default()
+        }
+    );
+}
+
+#[test]
+fn cell_cache_hits_nearby_samples() {
+    let cached = cached(64, 0);
+    cached.get(0.0, 0.0);
+    let first = cached.stats();
+    assert_eq!((first.cell_hits, first.cell_misses), (0, 9));
+    // same cell, every neighbor is cached
+    cached.get(0.0, 0.0);
+    let second = cached.stats();
+    assert_eq!((second.cell_hits, second.cell_misses), (9, 9));
+
+    cached.clear();
+    assert_eq!(cached.stats(), CacheStats::default());
+}
+
+#[test]
+fn shared_between_threads() {
+    let worley = worley();
+    let cached = cached(32, 0);
+    std::thread::scope(|scope| {
+        for t in 0..4 {
+            let (cached, worley) = (&cached, &worley);
+            scope.spawn(move || {
+                for i in 0..500 {
+                    let (x, z) = (i as f64 * 0.9 + t as f64 * 7.0, i as f64 * -0.4);
+                    assert_eq!(cached.get(x, z), worley.get(x, z));
+                }
+            });
+        }
+    });
+    let stats = cached.stats();
+    assert_eq!(stats.cell_hits + stats.cell_misses, 4 * 500 * 9);
+}
@@ -0,0 +1,76 @@
+//! Worley::cell_sites() finds the feature point Worley::get() sees at the site
+
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+    Plains,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest, Self::Snow, Self::Plains]
+    }
+}
+
+fn worley(
+    algorithm: AlgorithmVersion,
+    warp_strength: f32,
+    warp_frequency: f32,
+) -> Worley<Biome, SimpleBiomePicker<Biome>> {
+    Worley::builder()
+        .seed(21)
+        .zoom(25.0)
+        .sharpness(4.0)
+        .warp_strength(warp_strength)
+        .noise_frequency(warp_frequency)
+        .algorithm(algorithm)
+        .build()
+        .expect("valid worley")
+}
+
+#[test]
+fn site_is_the_cell_get_reports() {
+    let region = Region::new((-300.0, -300.0), (300.0, 300.0));
+    for algorithm in AlgorithmVersion::ALL {
+        // warps that can be inverted, unwarp_coords() is approximate for stronger ones
+        for (warp_strength, warp_frequency) in [(0.0, 1.0), (0.1, 1.0), (1.0, 0.05)] {
+            let worley = worley(algorithm, warp_strength, warp_frequency);
+            let sites = worley.cell_sites(region);
+            assert!(sites.len() > 100);
+            for site in sites {
+                let inspection = worley.inspect(site.x, site.z);
+                assert_eq!(inspection.cell, site.cell, "{algorithm:?} {warp_strength}");
+                // the feature point itself, nothing is closer
+                let nearest = &inspection.candidates[0];
+                assert_eq!(nearest.cell, site.cell);
+                assert!(
+                    nearest.distance < 1e-6,
+                    "{warp_strength} {site:?} {nearest:?}"
+                );
+                assert_eq!(nearest.biome, site.biome);
+            }
+        }
+    }
+}
+
+#[test]
+fn chunks_find_every_site_once() {
+    // strong folding warp, sites may be approximate but are still found once
+    let worley = worley(AlgorithmVersion::V1, 1.5, 1.0);
+    let whole = worley.cell_sites(Region::new((0.0, 0.0), (256.0, 256.0)));
+    let mut chunked = Vec::new();
+    for chunk_x in 0..4 {
+        for chunk_z in 0..4 {
+            chunked.extend(worley.cell_sites(Region::chunk(chunk_x, chunk_z, 64.0)));
+        }
+    }
+    assert_eq!(whole.len(), chunked.len());
+    for site in &whole {
+        assert_eq!(chunked.iter().filter(|s| s.cell == site.cell).count(), 1);
+    }
+}
@@ -0,0 +1,288 @@
+//! loading .worley.ron files: the shipped asset, migrations from older versions and errors
+
+use worley_biomes::config::{CONFIG_VERSION, ConfigError};
+use worley_biomes::distance_fn::DistanceFn;
+#[cfg(feature = "fast-noise")]
+use worley_biomes::noise::NoiseKind;
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+    Plains,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest, Self::Snow, Self::Plains]
+    }
+}
+
+use Biome::*;
+
+type BiomeWorley = Worley<Biome, SimpleBiomePicker<Biome>>;
+
+const ASSET: &str = "assets/test.worley.ron";
+
+// strongest biome first, bit exact
+#[cfg(feature = "fast-noise")]
+fn assert_weights(worley: &BiomeWorley, (x, z): (f64, f64), expected: &[(u64, Biome)]) {
+    let mut weights = worley.get(x, z).to_vec();
+    weights.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let weights: Vec<(u64, Biome)> = weights.iter().map(|(w, b)| (w.to_bits(), *b)).collect();
+    assert_eq!(weights, expected, "at {x}, {z}");
+}
+
+// the asset is a version 0 file, its noise_* fields are FastNoise settings
+#[cfg(feature = "fast-noise")]
+#[test]
+fn load_asset() {
+    let worley = BiomeWorley::from_file(ASSET).unwrap();
+    assert_eq!(worley.zoom, 40.0);
+    assert_eq!(worley.distance_fn_config, DistanceFn::Chebyshev);
+    assert_eq!(worley.k, 3);
+    assert_eq!(worley.algorithm, AlgorithmVersion::V0);
+    assert_eq!(worley.kill_percent_threshold, None);
+    assert_eq!(worley.warp_settings.strength, 0.6);
+    assert_eq!(worley.warp_settings.noise.kind(), NoiseKind::FastNoise);
+
+    // output of the same settings before config versioning existed
+    assert_weights(
+        &worley,
+        (0.0, 0.0),
+        &[
+            (0x3fddff4483e1666a, Plains),
+            (0x3fd8c6427b8606c9, Snow),
+            (0x3fc274f201312598, Desert),
+        ],
+    );
+    assert_weights(
+        &worley,
+        (123.5, -77.25),
+        &[
+            (0x3fefffe01a132380, Forest),
+            (0x3eea37d557b85442, Plains),
+            (0x3ec6b85e131eaa7b, Snow),
+        ],
+    );
+    assert_weights(
+        &worley,
+        (-1000.0, 250.75),
+        &[
+            (0x3fed6b29cbbd583d, Desert),
+            (0x3fa7fb6189c9ec0b, Plains),
+            (0x3fa15201ba60902b, Snow),
+        ],
+    );
+    assert_weights(
+        &worley,
+        (5000.3, -4321.9),
+        &[
+            (0x3fe3397b9c0215ad, Forest),
+            (0x3fd9464f8359b8f4, Plains),
+            (0x3f71ae512886ebca, Desert),
+        ],
+    );
+}
+
+// without "fast-noise" the FastNoise settings of the asset can't be loaded
+#[cfg(not(feature = "fast-noise"))]
+#[test]
+fn load_asset_without_fast_noise() {
+    assert_eq!(
+        BiomeWorley::from_file(ASSET).err(),
+        Some(ConfigError::MissingFeature {
+            feature: "fast-noise",
+            setting: "warp_settings.noise_*",
+        })
+    );
+}
+
+#[test]
+fn missing_file() {
+    let err = BiomeWorley::from_file("assets/missing.worley.ron").err();
+    assert!(
+        matches!(err, Some(ConfigError::Io { path, .. }) if path.ends_with("missing.worley.ron"))
+    );
+}
+
+#[test]
+fn migrate_version_0() {
+    // no version field, old names, no noise
+    let ron = r#"(
+        biome_generator: Weighted([(Desert, 0.5), (Snow, 0.5)]),
+        zoom: 25.0,
+        distance_fn: Manhattan,
+        sharpness: 4.0,
+        k: 2,
+        seed: 9,
+        warp_settings: (strength: 0.0),
+    )"#;
+    let worley = BiomeWorley::from_ron(ron).unwrap();
+    assert!(matches!(
+        &worley.biome_picker,
+        SimpleBiomePicker::Weighted(weights) if weights == &[(Desert, 0.5), (Snow, 0.5)]
+    ));
+    assert_eq!(worley.zoom, 25.0);
+    assert_eq!(worley.distance_fn_config, DistanceFn::Manhattan);
+    assert_eq!((worley.distance_fn)(3.0, -4.0), 7.0);
+    assert_eq!((worley.sharpness, worley.k, worley.seed), (4.0, 2, 9));
+    assert_eq!(worley.picker_seed, None);
+    assert_eq!(worley.algorithm, AlgorithmVersion::V0);
+    assert_eq!(worley.kill_percent_threshold, None);
+
+    // saved as the current version, loads back the same
+    let saved = worley.to_ron().unwrap();
+    assert!(
+        saved.contains(&format!("version: {CONFIG_VERSION}")),
+        "{saved}"
+    );
+    assert!(!saved.contains("biome_generator"), "{saved}");
+    let reloaded = BiomeWorley::from_ron(&saved).unwrap();
+    assert_eq!(reloaded.to_ron().unwrap(), saved);
+    for i in 0..100 {
+        let (x, z) = (i as f64 * 13.7, i as f64 * -5.1);
+        assert_eq!(reloaded.get(x, z), worley.get(x, z));
+    }
+}
+
+// version 0 warped with FastNoise::default() when the file has no noise
+#[test]
+fn version_0_default_noise() {
+    let ron = "(warp_settings: (strength: 0.6))";
+    #[cfg(feature = "fast-noise")]
+    assert_eq!(
+        BiomeWorley::from_ron(ron)
+            .unwrap()
+            .warp_settings
+            .noise
+            .kind(),
+        NoiseKind::FastNoise
+    );
+    #[cfg(not(feature = "fast-noise"))]
+    assert_eq!(
+        BiomeWorley::from_ron(ron).err(),
+        Some(ConfigError::MissingFeature {
+            feature: "fast-noise",
+            setting: "warp_settings.noise",
+        })
+    );
+    // the noise of unwarped files doesn't matter, they load without "fast-noise"
+    assert!(BiomeWorley::from_ron("(warp_settings: (strength: 0.0))").is_ok());
+
+    let ron = format!("(version: {CONFIG_VERSION}, warp_settings: (strength: 0.6))");
+    let worley = BiomeWorley::from_ron(&ron).unwrap();
+    assert!(matches!(worley.warp_settings.noise, Noise::Simplex(_)));
+}
+
+#[test]
+fn uniform_distribution_is_any() {
+    let worley = BiomeWorley::from_ron("(biome_generator: UniformDistribution)").unwrap();
+    assert!(matches!(worley.biome_picker, SimpleBiomePicker::Any));
+}
+
+#[test]
+fn current_version_keeps_defaults() {
+    let default = BiomeWorley::default();
+    let ron = format!(
+        "(version: {CONFIG_VERSION}, algorithm: {:?})",
+        default.algorithm
+    );
+    let worley = BiomeWorley::from_ron(&ron).unwrap();
+    assert_eq!(
+        worley.kill_percent_threshold,
+        default.kill_percent_threshold
+    );
+    assert_eq!(worley.to_ron().unwrap(), default.to_ron().unwrap());
+}
+
+#[test]
+fn migration_errors() {
+    assert_eq!(
+        BiomeWorley::from_ron("(distance_fn: Manhattan, distance_fn_config: Chebyshev)").err(),
+        Some(ConfigError::Conflict {
+            old: "distance_fn",
+            new: "distance_fn_config",
+        })
+    );
+    assert_eq!(
+        BiomeWorley::from_ron("(version: 999)").err(),
+        Some(ConfigError::UnsupportedVersion {
+            found: 999,
+            supported: CONFIG_VERSION,
+        })
+    );
+    assert_eq!(
+        BiomeWorley::from_ron("(k: 0)").err(),
+        Some(ConfigError::Invalid(WorleyError::InvalidK(0)))
+    );
+    assert!(matches!(
+        BiomeWorley::from_ron("(zoom: \"far\")"),
+        Err(ConfigError::Parse(_))
+    ));
+}
+
+// builder and Worley::from_ron() report the same errors
+#[test]
+fn builder_errors_match_from_ron() {
+    fn load(ron: &str, k: usize) -> Result<BiomeWorley, ConfigError> {
+        Ok(WorleyBuilder::from_ron(ron)?.k(k).build()?)
+    }
+    let ron = "(version: 0, zoom: 0.0)";
+    let err = load(ron, 3).err();
+    assert_eq!(err, BiomeWorley::from_ron(ron).err());
+    assert_eq!(
+        err,
+        Some(ConfigError::Invalid(WorleyError::InvalidZoom(0.0)))
+    );
+    assert!(std::error::Error::source(&err.unwrap()).is_some());
+
+    assert_eq!(
+        load("(zoom: 30.0)", 0).err(),
+        Some(ConfigError::Invalid(WorleyError::InvalidK(0)))
+    );
+    assert_eq!(load("(zoom: 30.0)", 4).unwrap().k, 4);
+}
+
+// a picker that can't be written, to_ron() reports it as Serialize
+#[derive(Default)]
+struct Unsaveable;
+
+impl worley_biomes::biome_picker::BiomePicker<Biome> for Unsaveable {
+    fn pick_biome(&self, _seed: u64, _cell_x: i32, _cell_z: i32) -> Biome {
+        Desert
+    }
+}
+
+impl serde::Serialize for Unsaveable {
+    fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("not saved"))
+    }
+}
+
+#[test]
+fn serialize_error() {
+    let err = Worley::<Biome, Unsaveable>::default().to_ron().err();
+    assert!(matches!(err, Some(ConfigError::Serialize(message)) if message.contains("not saved")));
+}
+
+// FastNoise doesn't save its permutation tables, loading rebuilds them from the seed
+#[cfg(feature = "fast-noise")]
+#[test]
+fn fast_noise_warp_round_trip() {
+    let worley: BiomeWorley = Worley::builder()
+        .zoom(10.0)
+        .warp_strength(4.0)
+        .warp_noise(bracket_fast_noise::prelude::FastNoise::seeded(5))
+        .noise_frequency(0.3)
+        .build()
+        .unwrap();
+    let loaded = BiomeWorley::from_ron(&worley.to_ron().unwrap()).unwrap();
+    for i in 0..200 {
+        let (x, z) = (i as f64 * 3.1, i as f64 * -1.7);
+        assert_eq!(loaded.get(x, z), worley.get(x, z), "at {x}, {z}");
+    }
+}
@@ -0,0 +1,76 @@
+//! Worley::pick_dithered() picks biomes as often as their weights say
+
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+    Plains,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest, Self::Snow, Self::Plains]
+    }
+}
+
+fn worley(algorithm: AlgorithmVersion) -> Worley<Biome, SimpleBiomePicker<Biome>> {
+    // low sharpness, wide borders where every block has to be rolled
+    Worley::builder()
+        .seed(11)
+        .zoom(30.0)
+        .sharpness(1.5)
+        .k(3)
+        .algorithm(algorithm)
+        .build()
+        .expect("valid worley")
+}
+
+#[test]
+fn pick_frequencies_match_weights() {
+    const SIZE: i32 = 256;
+    let samples = (SIZE * SIZE) as f64;
+    for algorithm in AlgorithmVersion::ALL {
+        let worley = worley(algorithm);
+        for pattern in [
+            DitherPattern::Hash,
+            DitherPattern::Ordered,
+            DitherPattern::BlueNoise,
+        ] {
+            let mut expected = [0.0; 4];
+            let mut picked = [0.0; 4];
+            for z in 0..SIZE {
+                for x in 0..SIZE {
+                    let (x, z) = (x as f64, z as f64);
+                    for (weight, biome) in worley.get(x, z) {
+                        expected[biome as usize] += weight;
+                    }
+                    let biome = worley.pick_dithered_with(x, z, pattern).unwrap();
+                    picked[biome as usize] += 1.0;
+                }
+            }
+            for biome in 0..4 {
+                let (expected, picked) = (expected[biome] / samples, picked[biome] / samples);
+                assert!(
+                    (expected - picked).abs() < 0.01,
+                    "{algorithm:?} {pattern:?} biome {biome}: picked {picked}, weight {expected}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn single_biome_is_always_picked() {
+    let worley = worley(AlgorithmVersion::V1);
+    for i in 0..1000 {
+        let (x, z) = (i as f64 * 1.3, i as f64 * -0.7);
+        let weights = worley.get(x, z);
+        if let [(_, biome)] = weights.as_slice() {
+            assert_eq!(worley.pick_dithered(x, z), Some(*biome));
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! builds the C API as a static library, links tests/c/ffi_test.c against it
+//! and checks the C program sees exactly what rust sees for the same config
+#![cfg(target_os = "linux")]
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use worley_biomes::ffi::WB_NO_BIOME;
+use worley_biomes::prelude::*;
+
+const NAMES: [&str; 4] = ["Desert", "Forest", "Snow", "Plains"];
+const POINTS: [(f64, f64); 4] = [
+    (0.0, 0.0),
+    (12.5, -40.25),
+    (-300.75, 88.0),
+    (1000.0, 1000.0),
+];
+const REGION: Region = Region {
+    min: (-64.0, -32.0),
+    max: (64.0, 32.0),
+};
+const WIDTH: usize = 4;
+const DEPTH: usize = 3;
+const STRIDE: usize = 4;
+
+fn run(command: &mut Command) -> String {
+    let output = command.output().expect("failed to start command");
+    assert!(
+        output.status.success(),
+        "{command:?} failed\n{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("utf-8 output")
+}
+
+// `cargo rustc` so the test doesn't depend on [lib] crate-type
+fn build_static_lib(target_dir: &Path) -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    run(Command::new(env!("CARGO"))
+        .arg("rustc")
+        .arg("--lib")
+        .arg("--features=ffi")
+        .arg("--crate-type=staticlib")
+        .arg("--manifest-path")
+        .arg(manifest_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(target_dir));
+    target_dir.join("debug").join("libworley_biomes.a")
+}
+
+fn build_c_test(target_dir: &Path, static_lib: &Path) -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let exe = target_dir.join("ffi_test");
+    run(
+        Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+            .arg(manifest_dir.join("tests/c/ffi_test.c"))
+            .arg("-I")
+            .arg(manifest_dir.join("include"))
+            .arg("-Wall")
+            .arg("-Werror")
+            .arg(static_lib)
+            .args(["-lpthread", "-ldl", "-lm", "-o"])
+            .arg(&exe),
+    );
+    exe
+}
+
+fn weight(out: &mut String, weight: f64, biome: u16) {
+    write!(out, " {:016x}:{biome}", weight.to_bits()).unwrap();
+}
+
+fn dominant(weights: &[(f64, BiomeId)]) -> u16 {
+    weights.dominant().map_or(WB_NO_BIOME, |biome| biome.0)
+}
+
+// what ffi_test.c should print
+fn expected(worley: &Worley<BiomeId, SimpleBiomePicker<BiomeId>>) -> String {
+    let mut out = String::new();
+    writeln!(out, "broken null error").unwrap();
+    writeln!(out, "mismatch null").unwrap();
+    writeln!(out, "biomes {}", NAMES.len()).unwrap();
+    for (i, name) in NAMES.iter().enumerate() {
+        writeln!(out, "biome {i} {name} {} {i}", name.len()).unwrap();
+    }
+    writeln!(out, "truncated {} {}", &NAMES[0][..2], NAMES[0].len()).unwrap();
+    writeln!(out, "unknown {WB_NO_BIOME}").unwrap();
+
+    for (i, (x, z)) in POINTS.iter().enumerate() {
+        let weights = worley.get(*x, *z);
+        write!(out, "sample {i}").unwrap();
+        for (w, biome) in weights.iter() {
+            weight(&mut out, *w, biome.0);
+        }
+        writeln!(out).unwrap();
+        writeln!(out, "dominant {i} {}", dominant(&weights)).unwrap();
+        let dithered = worley
+            .pick_dithered(*x, *z)
+            .map_or(WB_NO_BIOME, |biome| biome.0);
+        writeln!(out, "dithered {i} {dithered}").unwrap();
+    }
+
+    writeln!(out, "region too small -1").unwrap();
+    writeln!(out, "region zero stride -1").unwrap();
+    writeln!(out, "region 0").unwrap();
+    let grid = worley.sample_region(REGION, WIDTH, DEPTH);
+    for (i, sample) in grid.samples.iter().enumerate() {
+        write!(out, "cell {i}").unwrap();
+        for slot in 0..STRIDE {
+            match sample.get(slot) {
+                Some((w, biome)) => weight(&mut out, *w, biome.0),
+                None => weight(&mut out, 0.0, WB_NO_BIOME),
+            }
+        }
+        writeln!(out).unwrap();
+    }
+    write!(out, "dominant region 0").unwrap();
+    for sample in grid.samples.iter() {
+        write!(out, " {}", dominant(sample)).unwrap();
+    }
+    writeln!(out).unwrap();
+    out
+}
+
+#[test]
+fn c_api_matches_rust() {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi");
+    let static_lib = build_static_lib(&target_dir);
+    let exe = build_c_test(&target_dir, &static_lib);
+
+    let mut registry = BiomeRegistry::new();
+    for name in NAMES {
+        registry.register(name).unwrap();
+    }
+    let registry = registry.install();
+    let id = |name| registry.id(name).unwrap();
+    // weighted by name in the config, warped: everything the C side has to load
+    let worley: Worley<BiomeId, SimpleBiomePicker<BiomeId>> = Worley::builder()
+        .seed(7)
+        .zoom(30.0)
+        .sharpness(4.0)
+        .k(5)
+        .biome_picker(SimpleBiomePicker::Weighted(vec![
+            (id("Desert"), 0.2),
+            (id("Forest"), 0.4),
+            (id("Snow"), 0.1),
+            (id("Plains"), 0.3),
+        ]))
+        .warp_strength(4.0)
+        .warp_noise(SimplexNoise::default())
+        .build()
+        .expect("valid worley");
+    let config = target_dir.join("ffi_test.worley.ron");
+    std::fs::write(&config, worley.to_ron().unwrap()).unwrap();
+
+    let output = run(Command::new(exe).arg(&config).args(NAMES));
+    assert_eq!(output, expected(&worley));
+}
@@ -0,0 +1,98 @@
+//! snapshots of every AlgorithmVersion, each version's output is frozen once released.
+//! a failure here means a change reshapes existing worlds: add a new AlgorithmVersion
+//! for it instead of updating the snapshot
+
+use worley_biomes::hash::mix64;
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+    Plains,
+    Swamp,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[
+            Self::Desert,
+            Self::Forest,
+            Self::Snow,
+            Self::Plains,
+            Self::Swamp,
+        ]
+    }
+}
+
+fn worley(algorithm: AlgorithmVersion, seed: u64) -> Worley<Biome, SimpleBiomePicker<Biome>> {
+    // no warp, the noise library isn't part of the algorithm version
+    Worley::builder()
+        .seed(seed)
+        .zoom(40.0)
+        .sharpness(4.0)
+        .k(4)
+        .warp_strength(0.0)
+        .algorithm(algorithm)
+        .build()
+        .expect("valid worley")
+}
+
+// checksum of a 64x64 grid of samples: biomes, weights (to 1e-9) and for
+// ordered versions the order of the weights
+fn snapshot(worley: &Worley<Biome, SimpleBiomePicker<Biome>>, ordered: bool) -> u64 {
+    let mut checksum = 0u64;
+    for gz in -32..32 {
+        for gx in -32..32 {
+            let mut weights = worley.get(gx as f64 * 7.3, gz as f64 * 7.3).to_vec();
+            if !ordered {
+                weights.sort_by(|a, b| b.0.total_cmp(&a.0));
+            }
+            for (weight, biome) in weights {
+                checksum = mix64(checksum ^ biome as u64);
+                checksum = mix64(checksum ^ (weight * 1e9).round() as u64);
+            }
+        }
+    }
+    checksum
+}
+
+#[test]
+fn v1_snapshot() {
+    let expected = [
+        (0, 0xed95fcf4acefff8f),
+        (42, 0x10bea0f4065c26db),
+        (u64::MAX, 0x6b17f0dec9ef04b3),
+    ];
+    for (seed, checksum) in expected {
+        let actual = snapshot(&worley(AlgorithmVersion::V1, seed), true);
+        assert_eq!(actual, checksum, "V1 seed {seed}: {actual:#018x}");
+    }
+}
+
+// V0 hashes with FxHasher, which only matches these values on 64 bit targets.
+// the order of V0 weights isn't specified
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn v0_snapshot() {
+    let expected = [
+        (0, 0x4df5c0ee913c18bf),
+        (42, 0xec2e471b5e924626),
+        (u64::MAX, 0x21f564fdcdb9a66d),
+    ];
+    for (seed, checksum) in expected {
+        let actual = snapshot(&worley(AlgorithmVersion::V0, seed), false);
+        assert_eq!(actual, checksum, "V0 seed {seed}: {actual:#018x}");
+    }
+}
+
+#[test]
+fn v1_weights_are_closest_first() {
+    let worley = worley(AlgorithmVersion::V1, 7);
+    for i in 0..256 {
+        let weights = worley.get(i as f64 * 3.1, i as f64 * -1.7);
+        assert!(weights.windows(2).all(|w| w[0].0 >= w[1].0), "{weights:?}");
+    }
+}
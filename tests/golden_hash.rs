@@ -0,0 +1,328 @@
+//! known outputs for known seeds, a failure here means worlds generated
+//! with the same seed no longer match the ones generated before
+
+use rand::RngCore;
+use worley_biomes::biome_picker::BiomePicker;
+use worley_biomes::hash::{SplitMix64, portable_hash, portable_hash_bytes};
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+    Plains,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest, Self::Snow, Self::Plains]
+    }
+}
+
+use Biome::*;
+
+fn worley(algorithm: AlgorithmVersion) -> Worley<Biome, SimpleBiomePicker<Biome>> {
+    // no warp, the goldens only cover cell hashing
+    Worley::builder()
+        .seed(42)
+        .zoom(50.0)
+        .sharpness(2.0)
+        .kill_percent_threshold(None)
+        .warp_strength(0.0)
+        .algorithm(algorithm)
+        .build()
+        .expect("valid worley")
+}
+
+// strongest biome first, the order of Worley::get() isn't part of the output
+fn sorted_weights(weights: &[(f64, Biome)]) -> Vec<(f64, Biome)> {
+    let mut weights = weights.to_vec();
+    weights.sort_by(|a, b| b.0.total_cmp(&a.0));
+    weights
+}
+
+fn assert_weights(actual: &[(f64, Biome)], expected: &[(f64, Biome)]) {
+    let actual = sorted_weights(actual);
+    assert_eq!(actual.len(), expected.len(), "{actual:?} != {expected:?}");
+    for ((a, a_biome), (e, e_biome)) in actual.iter().zip(expected) {
+        assert_eq!(a_biome, e_biome, "{actual:?} != {expected:?}");
+        assert!((a - e).abs() < 1e-9, "{actual:?} != {expected:?}");
+    }
+}
+
+#[test]
+fn portable_hash_golden() {
+    assert_eq!(portable_hash(0, 0, 0), 0x48218226ff3cd4bf);
+    assert_eq!(portable_hash(42, 1, -1), 0xa70c2c559aa31666);
+    assert_eq!(portable_hash(12345, -100, 37), 0xdce8f76678f21b56);
+    assert_eq!(
+        portable_hash(u64::MAX, i32::MIN, i32::MAX),
+        0x14aa3c95b0e5a529
+    );
+}
+
+#[test]
+fn portable_hash_bytes_golden() {
+    assert_eq!(portable_hash_bytes(b""), 0xe220a8397b1dcdaf);
+    assert_eq!(portable_hash_bytes(b"a"), 0x61ee1be14d221812);
+    assert_eq!(portable_hash_bytes(b"exactly8"), 0xb124847270618ea6);
+    assert_eq!(portable_hash_bytes(b"mossy hills"), 0x0232cd20ef452eea);
+}
+
+#[test]
+fn world_seed_golden() {
+    let world = WorldSeed::from("mossy hills");
+    assert_eq!(world, WorldSeed(0x0232cd20ef452eea));
+    assert_eq!(world.layer(SeedLayer::Picker), 0x3170fcdced9e262c);
+    assert_eq!(world.layer(SeedLayer::FeaturePoints), 0xa04faf0e5e8a30f3);
+    assert_eq!(world.layer(SeedLayer::Warp), 0xd9e3991a73e8855e);
+    assert_eq!(world.layer(SeedLayer::Decoration), 0x9b1567a4181b8dca);
+    assert_eq!(
+        world.derive("decoration").derive("trees"),
+        WorldSeed(0x8817ccfa5d151822)
+    );
+    // numbers are taken as is
+    assert_eq!(WorldSeed::from(" 42 "), WorldSeed(42));
+    assert_eq!(WorldSeed::from("-1"), WorldSeed(u64::MAX));
+}
+
+#[test]
+fn picker_seed_keeps_cells() {
+    let mut worley = worley(AlgorithmVersion::V1);
+    worley.set_world_seed(WorldSeed::from("mossy hills"));
+    let region = Region::new((-200.0, -200.0), (200.0, 200.0));
+    let before = worley.cell_sites(region);
+    worley.picker_seed = Some(7);
+    let after = worley.cell_sites(region);
+    assert_eq!(before.len(), after.len());
+    for (a, b) in before.iter().zip(&after) {
+        assert_eq!((a.cell, a.x, a.z), (b.cell, b.x, b.z));
+    }
+    assert!(before.iter().zip(&after).any(|(a, b)| a.biome != b.biome));
+}
+
+#[test]
+fn split_mix_golden() {
+    // reference output of SplitMix64 seeded with 0
+    let mut rng = SplitMix64::new(0);
+    assert_eq!(rng.next_u64(), 0xe220a8397b1dcdaf);
+    assert_eq!(rng.next_u64(), 0x6e789e6aa1b965f4);
+}
+
+#[test]
+fn v1_picker_golden() {
+    let weighted = SimpleBiomePicker::Weighted(vec![(Desert, 0.5), (Snow, 0.25), (Plains, 0.25)]);
+    let picks: Vec<Biome> = (0..8)
+        .map(|i| weighted.pick_biome_versioned(7, i, -i, HashVersion::V1))
+        .collect();
+    assert_eq!(
+        picks,
+        [Desert, Snow, Desert, Snow, Plains, Desert, Desert, Snow]
+    );
+
+    let picks: Vec<Biome> = (0..8)
+        .map(|i| SimpleBiomePicker::Any.pick_biome_versioned(7, i, -i, HashVersion::V1))
+        .collect();
+    assert_eq!(
+        picks,
+        [Snow, Snow, Forest, Plains, Forest, Plains, Snow, Snow]
+    );
+}
+
+#[test]
+fn v1_worley_golden() {
+    let worley = worley(AlgorithmVersion::V1);
+    assert_weights(
+        &worley.get(0.0, 0.0),
+        &[
+            (0.5949959464050987, Plains),
+            (0.23743707235299574, Plains),
+            (0.16756698124190572, Desert),
+        ],
+    );
+    assert_weights(
+        &worley.get(123.5, -77.25),
+        &[
+            (0.7814276293374433, Desert),
+            (0.1790013232313245, Forest),
+            (0.039571047431232305, Forest),
+        ],
+    );
+    assert_weights(
+        &worley.get(-1000.0, 2500.0),
+        &[
+            (0.9439101086055987, Forest),
+            (0.03307408180505365, Forest),
+            (0.02301580958934778, Desert),
+        ],
+    );
+    assert_eq!(worley.pick_dithered(0.0, 0.0), Some(Desert));
+    assert_eq!(worley.pick_dithered(-1000.0, 2500.0), Some(Forest));
+}
+
+// V0 hashes with FxHasher, which only matches these values on 64 bit targets
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn v0_worley_golden() {
+    let worley = worley(AlgorithmVersion::V0);
+    assert_weights(
+        &worley.get(0.0, 0.0),
+        &[
+            (0.9922956794514248, Desert),
+            (0.005087978723524576, Plains),
+            (0.002616341825050455, Desert),
+        ],
+    );
+    assert_weights(
+        &worley.get(-1000.0, 2500.0),
+        &[
+            (0.7404397197019724, Desert),
+            (0.19025219317755754, Forest),
+            (0.06930808712047021, Plains),
+        ],
+    );
+
+    let weighted = SimpleBiomePicker::Weighted(vec![(Desert, 0.5), (Snow, 0.25), (Plains, 0.25)]);
+    let picks: Vec<Biome> = (0..8)
+        .map(|i| weighted.pick_biome_versioned(7, i, -i, HashVersion::V0))
+        .collect();
+    assert_eq!(
+        picks,
+        [Desert, Plains, Plains, Desert, Desert, Plains, Snow, Snow]
+    );
+}
+
+// output of worley_biomes before AlgorithmVersion existed, bit for bit.
+// needs std's powf like the original, and FxHasher's 64 bit output
+#[cfg(all(feature = "std", target_pointer_width = "64"))]
+#[test]
+fn v0_matches_original_bits() {
+    fn assert_bits(worley: &Worley<Biome, SimpleBiomePicker<Biome>>, expected: &[&[(u64, Biome)]]) {
+        let positions = [
+            (0.0, 0.0),
+            (123.5, -77.25),
+            (-1000.0, 2500.0),
+            (31.7, -999.1),
+        ];
+        for ((x, z), expected) in positions.into_iter().zip(expected) {
+            let bits: Vec<(u64, Biome)> = sorted_weights(&worley.get(x, z))
+                .iter()
+                .map(|(weight, biome)| (weight.to_bits(), *biome))
+                .collect();
+            assert_eq!(bits, *expected, "at {x}, {z}");
+        }
+    }
+
+    let weighted: Worley<Biome, SimpleBiomePicker<Biome>> = Worley::builder()
+        .biome_picker(SimpleBiomePicker::Weighted(vec![
+            (Desert, 0.4),
+            (Forest, 0.3),
+            (Snow, 0.2),
+            (Plains, 0.1),
+        ]))
+        .seed(42)
+        .zoom(50.0)
+        .sharpness(1.5)
+        .k(4)
+        .kill_percent_threshold(Some(0.05))
+        .build()
+        .expect("valid worley");
+    assert_bits(
+        &weighted,
+        &[
+            &[(0x3ff0000000000000, Desert)],
+            &[(0x3ff0000000000000, Desert)],
+            &[
+                (0x3fe3405c6ba10aa9, Forest),
+                (0x3fcbca780236d7e3, Desert),
+                (0x3fba101bfd53a6ea, Snow),
+                (0x3fb45810a1365403, Desert),
+            ],
+            &[
+                (0x3fdc850c326bd23c, Plains),
+                (0x3fd2ecfaea259cef, Snow),
+                (0x3fc793b30fed60c3, Desert),
+                (0x3fb3107d6ddf81c4, Snow),
+            ],
+        ],
+    );
+
+    // every other setting left at its default, which has to stay the original output
+    let default: Worley<Biome, SimpleBiomePicker<Biome>> = Worley {
+        seed: 7,
+        sharpness: 7.5,
+        ..Default::default()
+    };
+    assert_bits(
+        &default,
+        &[
+            &[(0x3feecb0b479e5a93, Plains), (0x3fa34f4b861a56c5, Forest)],
+            &[(0x3ff0000000000000, Forest)],
+            &[(0x3ff0000000000000, Snow)],
+            &[
+                (0x3fed4b250052ee95, Snow),
+                (0x3fa814f10d858817, Desert),
+                (0x3fa338beed4b8ea2, Forest),
+            ],
+        ],
+    );
+}
+
+// pickers written against the original trait only implement pick_biome()
+struct Checkerboard;
+
+impl BiomePicker<Biome> for Checkerboard {
+    fn pick_biome(&self, _seed: u64, cell_x: i32, cell_z: i32) -> Biome {
+        if (cell_x + cell_z) % 2 == 0 {
+            Desert
+        } else {
+            Snow
+        }
+    }
+}
+
+impl Default for Checkerboard {
+    fn default() -> Self {
+        Checkerboard
+    }
+}
+
+#[test]
+fn pick_biome_is_v0() {
+    let weighted = SimpleBiomePicker::Weighted(vec![(Desert, 0.5), (Snow, 0.25), (Plains, 0.25)]);
+    for i in 0..64 {
+        assert_eq!(
+            weighted.pick_biome(7, i, -i),
+            weighted.pick_biome_versioned(7, i, -i, HashVersion::V0)
+        );
+    }
+    assert_eq!(
+        Worley::<Biome, SimpleBiomePicker<Biome>>::default().algorithm,
+        AlgorithmVersion::V0
+    );
+    assert_eq!(HashVersion::default(), HashVersion::V0);
+
+    for algorithm in AlgorithmVersion::ALL {
+        let worley = Worley::<Biome, Checkerboard>::builder()
+            .seed(3)
+            .zoom(1.0)
+            .warp_strength(0.0)
+            .algorithm(algorithm)
+            .build()
+            .expect("valid worley");
+        for site in worley.cell_sites(Region::new((-4.0, -4.0), (4.0, 4.0))) {
+            let (x, z) = site.cell;
+            assert_eq!(site.biome, Checkerboard.pick_biome(0, x, z));
+        }
+    }
+}
+
+#[test]
+fn custom_warp_noise_is_not_reseeded() {
+    let mut worley = worley(AlgorithmVersion::V1);
+    assert!(worley.set_world_seed(WorldSeed::from("mossy hills")));
+    worley.warp_settings.noise = Noise::custom(NoiseFn(|x: f32, z: f32| (x - z).sin()));
+    assert!(!worley.set_world_seed(WorldSeed::from("mossy hills")));
+}
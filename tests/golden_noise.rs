@@ -0,0 +1,136 @@
+//! known outputs of the builtin warp noises, saved configs that use them
+//! must keep warping the same way
+
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest, Self::Snow]
+    }
+}
+
+#[test]
+fn value_noise_golden() {
+    let noise = ValueNoise {
+        seed: 42,
+        ..Default::default()
+    };
+    assert_eq!(noise.get_noise(0.0, 0.0), 0.5515556);
+    assert_eq!(noise.get_noise(1.25, -3.5), 0.16881229);
+    assert_eq!(noise.get_noise(-100.7, 42.3), 0.09026166);
+}
+
+#[test]
+fn simplex_noise_golden() {
+    let noise = SimplexNoise {
+        seed: 42,
+        ..Default::default()
+    };
+    assert_eq!(noise.get_noise(0.0, 0.0), 0.0);
+    assert_eq!(noise.get_noise(1.25, -3.5), 0.15267506);
+    assert_eq!(noise.get_noise(-100.7, 42.3), 0.05375085);
+}
+
+#[test]
+fn custom_noise_warps() {
+    let straight: Worley<Biome, SimpleBiomePicker<Biome>> =
+        Worley::builder().zoom(1.0).build().expect("valid worley");
+    let warped: Worley<Biome, SimpleBiomePicker<Biome>> = Worley::builder()
+        .zoom(1.0)
+        .warp_strength(1.0)
+        .warp_noise(Noise::custom(NoiseFn(|_x: f32, _z: f32| 0.5)))
+        .build()
+        .expect("valid worley");
+    // a constant offset of 0.5 moves every sample by (0.5, 0.5) cells
+    for i in 0..64 {
+        let (x, z) = (i as f64 * 3.0, i as f64 * -2.0);
+        assert_eq!(warped.get(x, z), straight.get(x + 0.5, z + 0.5));
+    }
+}
+
+#[test]
+fn cloned_worley_warps_the_same() {
+    // the default noise, Simplex with or without "fast-noise"
+    let worley: Worley<Biome, SimpleBiomePicker<Biome>> = Worley::builder()
+        .zoom(20.0)
+        .warp_strength(4.0)
+        .noise_seed(7)
+        .noise_frequency(0.3)
+        .fractal_octaves(4)
+        .fractal_gain(0.7)
+        .build()
+        .expect("valid worley");
+    let custom: Worley<Biome, SimpleBiomePicker<Biome>> = Worley::builder()
+        .warp_strength(1.0)
+        .warp_noise(Noise::custom(NoiseFn(|x: f32, z: f32| (x * z).sin())))
+        .build()
+        .expect("valid worley");
+    for original in [worley, custom] {
+        let copy = original.clone();
+        for i in 0..64 {
+            let (x, z) = (i as f64 * 3.7, i as f64 * -2.3);
+            assert_eq!(copy.get(x, z), original.get(x, z));
+        }
+    }
+}
+
+// FastNoise has no Clone, every setting has to survive Noise::clone
+#[cfg(feature = "fast-noise")]
+#[test]
+fn cloned_fast_noise_keeps_every_setting() {
+    use bracket_fast_noise::prelude::*;
+
+    let mut cellular = FastNoise::seeded(99);
+    cellular.set_noise_type(NoiseType::Cellular);
+    cellular.set_frequency(0.4);
+    cellular.set_cellular_distance_function(CellularDistanceFunction::Manhattan);
+    cellular.set_cellular_return_type(CellularReturnType::Distance);
+    cellular.set_cellular_distance_indices(0, 3);
+    cellular.set_cellular_jitter(0.3);
+    let mut fractal = FastNoise::seeded(5);
+    fractal.set_noise_type(NoiseType::CubicFractal);
+    fractal.set_fractal_type(FractalType::RigidMulti);
+    fractal.set_fractal_octaves(5);
+    fractal.set_fractal_gain(0.8);
+    fractal.set_fractal_lacunarity(2.5);
+    fractal.set_interp(Interp::Linear);
+    let mut perturbed = FastNoise::seeded(3);
+    perturbed.set_noise_type(NoiseType::PerlinFractal);
+    perturbed.set_fractal_type(FractalType::Billow);
+    perturbed.set_gradient_perterb_amp(3.0);
+    perturbed.set_frequency(0.05);
+
+    for noise in [cellular, fractal, perturbed] {
+        let original: Worley<Biome, SimpleBiomePicker<Biome>> = Worley::builder()
+            .warp_strength(2.0)
+            .warp_noise(noise)
+            .build()
+            .expect("valid worley");
+        let copy = original.clone();
+        let (Noise::FastNoise(a), Noise::FastNoise(b)) =
+            (&original.warp_settings.noise, &copy.warp_settings.noise)
+        else {
+            panic!("expected FastNoise");
+        };
+        for i in 0..64 {
+            let (x, z) = (i as f32 * 3.7, i as f32 * -2.3);
+            assert_eq!(a.get_noise(x, z).to_bits(), b.get_noise(x, z).to_bits());
+            assert_eq!(
+                a.get_noise3d(x, z, 1.5).to_bits(),
+                b.get_noise3d(x, z, 1.5).to_bits()
+            );
+        }
+        // the saved config covers the private fields get_noise may not reach
+        #[cfg(feature = "serde")]
+        assert_eq!(copy.to_ron().ok(), original.to_ron().ok());
+    }
+}
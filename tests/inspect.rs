@@ -0,0 +1,100 @@
+//! Worley::inspect() explains Worley::get(), the returned weights have to match exactly
+
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+    Plains,
+    Swamp,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[
+            Self::Desert,
+            Self::Forest,
+            Self::Snow,
+            Self::Plains,
+            Self::Swamp,
+        ]
+    }
+}
+
+fn worley(
+    algorithm: AlgorithmVersion,
+    k: usize,
+    kill_percent_threshold: Option<f64>,
+) -> Worley<Biome, SimpleBiomePicker<Biome>> {
+    Worley::builder()
+        .seed(5)
+        .zoom(20.0)
+        .sharpness(3.0)
+        .k(k)
+        .kill_percent_threshold(kill_percent_threshold)
+        .warp_strength(1.5)
+        .algorithm(algorithm)
+        .build()
+        .expect("valid worley")
+}
+
+fn positions() -> impl Iterator<Item = (f64, f64)> {
+    (0..400).map(|i| (i as f64 * 3.7 - 300.0, (i * i % 97) as f64 * 5.3 - 200.0))
+}
+
+#[test]
+fn weights_match_get() {
+    for algorithm in AlgorithmVersion::ALL {
+        for k in [1, 2, 3, 5, 9] {
+            for threshold in [None, Some(0.01), Some(0.2)] {
+                let worley = worley(algorithm, k, threshold);
+                for (x, z) in positions() {
+                    let inspection = worley.inspect(x, z);
+                    assert_eq!(
+                        inspection.weights.as_slice(),
+                        worley.get(x, z).as_slice(),
+                        "{algorithm:?} k {k} threshold {threshold:?} at {x}, {z}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn candidates_explain_dropped_biomes() {
+    let mut killed = 0;
+    for k in [2, 3, 5] {
+        let worley = worley(AlgorithmVersion::V1, k, Some(0.2));
+        for (x, z) in positions() {
+            let inspection = worley.inspect(x, z);
+            let candidates = &inspection.candidates;
+            assert_eq!(candidates.len(), 9);
+            assert!(
+                candidates
+                    .windows(2)
+                    .all(|w| w[0].distance <= w[1].distance)
+            );
+            assert!(
+                candidates
+                    .iter()
+                    .all(|c| (c.cell.0 - inspection.cell.0).abs() <= 1
+                        && (c.cell.1 - inspection.cell.1).abs() <= 1)
+            );
+
+            let fates = |fate| candidates.iter().filter(move |c| c.fate == fate);
+            assert_eq!(fates(CandidateFate::BeyondK).count(), 9 - k);
+            assert!(fates(CandidateFate::BeyondK).all(|c| c.weight.is_none()));
+            assert_eq!(fates(CandidateFate::Kept).count(), inspection.weights.len());
+            assert!(fates(CandidateFate::Killed).all(|c| c.weight.unwrap() <= 0.2));
+            killed += fates(CandidateFate::Killed).count();
+
+            let weight_sum: f64 = candidates.iter().filter_map(|c| c.weight).sum();
+            assert!((weight_sum - 1.0).abs() < 1e-9);
+        }
+    }
+    assert!(killed > 0);
+}
@@ -0,0 +1,138 @@
+//! builds the python module, runs tests/python/test_worley_biomes.py with it
+//! and checks python sees exactly what rust sees for the same config.
+//! needs python 3 with numpy, set PYTHON to pick the interpreter
+#![cfg(target_os = "linux")]
+
+use std::fmt::Write;
+use std::path::Path;
+use std::process::Command;
+
+use worley_biomes::prelude::*;
+
+const NAMES: [&str; 4] = ["Desert", "Forest", "Snow", "Plains"];
+const POINTS: [(f64, f64); 4] = [
+    (0.0, 0.0),
+    (12.5, -40.25),
+    (-300.75, 88.0),
+    (1000.0, 1000.0),
+];
+
+fn python() -> String {
+    std::env::var("PYTHON").unwrap_or_else(|_| "python3".to_string())
+}
+
+fn run(command: &mut Command) -> String {
+    let output = command.output().expect("failed to start command");
+    assert!(
+        output.status.success(),
+        "{command:?} failed\n{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("utf-8 output")
+}
+
+// `cargo rustc` so the test doesn't depend on [lib] crate-type,
+// copied to the name python imports
+fn build_module(target_dir: &Path) {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    run(Command::new(env!("CARGO"))
+        .arg("rustc")
+        .arg("--lib")
+        .arg("--features=python")
+        .arg("--crate-type=cdylib")
+        .arg("--manifest-path")
+        .arg(manifest_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(target_dir)
+        .env("PYO3_PYTHON", python())
+        .env("PYO3_BUILD_EXTENSION_MODULE", "1"));
+    std::fs::copy(
+        target_dir.join("debug").join("libworley_biomes.so"),
+        target_dir.join("worley_biomes.so"),
+    )
+    .expect("copy python module");
+}
+
+fn weights(out: &mut String, label: &str, weights: impl Iterator<Item = (f64, i32)>) {
+    write!(out, "{label}").unwrap();
+    for (weight, biome) in weights {
+        write!(out, " {:016x}:{biome}", weight.to_bits()).unwrap();
+    }
+    writeln!(out).unwrap();
+}
+
+fn biome(biome: Option<BiomeId>) -> String {
+    biome.map_or("None".to_string(), |biome| biome.0.to_string())
+}
+
+// what test_worley_biomes.py should print
+fn expected(worley: &Worley<BiomeId, SimpleBiomePicker<BiomeId>>) -> String {
+    let mut out = String::new();
+    writeln!(out, "biomes {}", NAMES.join(" ")).unwrap();
+    for (i, (x, z)) in POINTS.iter().enumerate() {
+        let sample = worley.get(*x, *z);
+        let label = format!("sample {i}");
+        weights(
+            &mut out,
+            &label,
+            sample.iter().map(|(w, b)| (*w, b.0 as i32)),
+        );
+        writeln!(out, "dominant {i} {}", biome(sample.dominant())).unwrap();
+        writeln!(out, "dithered {i} {}", biome(worley.pick_dithered(*x, *z))).unwrap();
+    }
+
+    let region = Region::new((-64.0, -32.0), (64.0, 32.0));
+    let grid = worley.sample_region(region, 4, 3);
+    for z in 0..3 {
+        for x in 0..4 {
+            let sample = grid.get(x, z);
+            let padded = (0..worley.k).map(|slot| match sample.get(slot) {
+                Some((w, b)) => (*w, b.0 as i32),
+                None => (0.0, -1),
+            });
+            weights(&mut out, &format!("cell {z} {x}"), padded);
+        }
+    }
+    write!(out, "dominant region").unwrap();
+    for sample in grid.samples.iter() {
+        write!(out, " {}", sample.dominant().map_or(-1, |b| b.0 as i32)).unwrap();
+    }
+    writeln!(out).unwrap();
+    writeln!(out, "ok").unwrap();
+    out
+}
+
+#[test]
+fn python_matches_rust() {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("python");
+    build_module(&target_dir);
+
+    let registry = BiomeRegistry::install_names(&NAMES).unwrap();
+    let id = |name| registry.id(name).unwrap();
+    let worley: Worley<BiomeId, SimpleBiomePicker<BiomeId>> = Worley::builder()
+        .seed(11)
+        .zoom(30.0)
+        .sharpness(4.0)
+        .k(5)
+        .biome_picker(SimpleBiomePicker::Weighted(vec![
+            (id("Desert"), 0.2),
+            (id("Forest"), 0.4),
+            (id("Snow"), 0.1),
+            (id("Plains"), 0.3),
+        ]))
+        .warp_strength(4.0)
+        .warp_noise(SimplexNoise::default())
+        .build()
+        .expect("valid worley");
+    let config = target_dir.join("python_test.worley.ron");
+    std::fs::write(&config, worley.to_ron().unwrap()).unwrap();
+
+    let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/python/test_worley_biomes.py");
+    let output = run(Command::new(python())
+        .arg(script)
+        .arg(&config)
+        .args(NAMES)
+        .env("PYTHONPATH", &target_dir));
+    assert_eq!(output, expected(&worley));
+}
@@ -0,0 +1,75 @@
+# uses the python module the way a notebook would, driven by tests/python.rs:
+# python test_worley_biomes.py <config.worley.ron> <biome names...>
+# prints every result, weights as f64 bits so the rust side can compare exactly
+import struct
+import sys
+
+import numpy as np
+
+import worley_biomes
+
+
+def bits(weight):
+    return "%016x" % struct.unpack("<Q", struct.pack("<d", weight))[0]
+
+
+def weights_line(label, biomes, weights):
+    return label + "".join(" %s:%d" % (bits(w), b) for b, w in zip(biomes, weights))
+
+
+config, names = sys.argv[1], sys.argv[2:]
+worley = worley_biomes.Worley.load(config, names)
+print("biomes", " ".join(worley.biomes))
+
+points = [(0.0, 0.0), (12.5, -40.25), (-300.75, 88.0), (1000.0, 1000.0)]
+for i, (x, z) in enumerate(points):
+    sample = worley.sample(x, z)
+    print(weights_line("sample %d" % i, [b for _, b in sample], [w for w, _ in sample]))
+    print("dominant %d %s" % (i, worley.dominant(x, z)))
+    print("dithered %d %s" % (i, worley.pick_dithered(x, z)))
+
+# the array version of sample(), padded with -1 / 0.0 to k
+xs = np.array([x for x, _ in points])
+zs = np.array([z for _, z in points])
+biomes, weights = worley.sample_points(xs, zs)
+assert biomes.shape == (len(points), worley.k) and biomes.dtype == np.int32
+for i, (x, z) in enumerate(points):
+    sample = worley.sample(x, z)
+    padded = sample + [(0.0, -1)] * (worley.k - len(sample))
+    assert biomes[i].tolist() == [b for _, b in padded]
+    assert weights[i].tolist() == [w for w, _ in padded]
+
+biomes, weights = worley.sample_region(-64.0, -32.0, 64.0, 32.0, 4, 3)
+assert biomes.shape == (3, 4, worley.k) and weights.shape == (3, 4, worley.k)
+for z in range(3):
+    for x in range(4):
+        print(weights_line("cell %d %d" % (z, x), biomes[z, x], weights[z, x]))
+dominant = worley.dominant_region(-64.0, -32.0, 64.0, 32.0, 4, 3)
+print("dominant region", " ".join(str(b) for b in dominant.flatten()))
+
+# saved configs load back to the same worley
+reloaded = worley_biomes.Worley.from_ron(worley.to_ron(), names)
+assert all(reloaded.sample(x, z) == worley.sample(x, z) for x, z in points)
+
+# invalid settings raise and leave the worley unchanged
+for name, value in [("k", 0), ("zoom", -1.0), ("distance_fn", "Nowhere")]:
+    before = getattr(worley, name)
+    try:
+        setattr(worley, name, value)
+        raise AssertionError("%s = %r was accepted" % (name, value))
+    except ValueError:
+        assert getattr(worley, name) == before
+try:
+    worley.use_weighted([(0, 0.5)])
+    raise AssertionError("weights not adding up to 1.0 were accepted")
+except ValueError:
+    pass
+
+# the biome list is shared by the whole process
+try:
+    worley_biomes.Worley(["Nowhere"])
+    raise AssertionError("a different biome list was accepted")
+except ValueError:
+    pass
+assert worley_biomes.Worley(names, seed=3).biome_id(names[1]) == 1
+print("ok")
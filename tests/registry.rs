@@ -0,0 +1,102 @@
+//! BiomeRegistry lookups and BiomeId without an installed registry.
+//! nothing in this file installs a registry, see registry_install.rs for that
+
+use worley_biomes::biome_picker::BiomePicker;
+use worley_biomes::prelude::*;
+use worley_biomes::registry::RegistryError;
+
+fn registry() -> BiomeRegistry {
+    let mut registry = BiomeRegistry::new();
+    for name in ["desert", "forest", "snow"] {
+        registry.register(name).unwrap();
+    }
+    registry
+}
+
+#[test]
+fn unknown_names() {
+    let registry = registry();
+    assert_eq!(registry.id("forest"), Some(BiomeId(1)));
+    assert_eq!(registry.id("lava"), None);
+    assert_eq!(
+        registry.try_id("lava"),
+        Err(RegistryError::Unknown {
+            name: "lava".to_string(),
+            known: vec!["desert".into(), "forest".into(), "snow".into()],
+        })
+    );
+    assert_eq!(
+        registry.try_id("lava").unwrap_err().to_string(),
+        "unknown biome \"lava\", known biomes: [\"desert\", \"forest\", \"snow\"]"
+    );
+    assert_eq!(
+        registry.clone().register("snow"),
+        Err(RegistryError::Duplicate("snow".to_string()))
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn unknown_names_in_configs() {
+    let ron = r#"(biome_picker: AnyOf(["desert", "lava"]))"#;
+    let err = registry()
+        .scope(|| Worley::<BiomeId, SimpleBiomePicker<BiomeId>>::from_ron(ron))
+        .err()
+        .expect("lava isn't registered");
+    let err = err.to_string();
+    assert!(err.contains("unknown biome \"lava\""), "{err}");
+    assert!(err.contains("\"desert\", \"forest\", \"snow\""), "{err}");
+
+    let ron = r#"(biome_picker: AnyOf(["desert", 7]))"#;
+    let err = registry()
+        .scope(|| Worley::<BiomeId, SimpleBiomePicker<BiomeId>>::from_ron(ron))
+        .err()
+        .expect("index out of range");
+    assert!(
+        err.to_string().contains("biome index 7 out of range"),
+        "{err}"
+    );
+
+    let worley = registry()
+        .scope(|| {
+            Worley::<BiomeId, SimpleBiomePicker<BiomeId>>::from_ron(
+                r#"(biome_picker: AnyOf(["snow", "desert"]))"#,
+            )
+        })
+        .unwrap();
+    assert!(matches!(
+        &worley.biome_picker,
+        SimpleBiomePicker::AnyOf(ids) if ids == &[BiomeId(2), BiomeId(0)]
+    ));
+}
+
+#[test]
+fn uninstalled_registry() {
+    assert!(BiomeRegistry::installed().is_none());
+    assert!(BiomeId::variants().is_empty());
+
+    let worley: Worley<BiomeId, SimpleBiomePicker<BiomeId>> = Worley::default();
+    assert_eq!(worley.validate(), Err(WorleyError::NoVariants));
+    // sampling anyway doesn't panic
+    for (weight, biome) in worley.get(12.0, -40.0) {
+        assert!(weight.is_finite());
+        assert_eq!(biome, BiomeId::default());
+    }
+    assert_eq!(
+        SimpleBiomePicker::<BiomeId>::Any.pick_biome_versioned(1, 2, 3, HashVersion::V1),
+        BiomeId::default()
+    );
+
+    #[cfg(feature = "serde")]
+    {
+        let err = Worley::<BiomeId, SimpleBiomePicker<BiomeId>>::from_ron(
+            r#"(biome_picker: AnyOf(["desert"]))"#,
+        )
+        .err()
+        .expect("no registry to look names up in");
+        assert!(
+            err.to_string().contains("no BiomeRegistry installed"),
+            "{err}"
+        );
+    }
+}
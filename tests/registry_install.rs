@@ -0,0 +1,41 @@
+//! BiomeRegistry::install() is process wide, so it gets its own test binary
+
+use worley_biomes::prelude::*;
+use worley_biomes::registry::RegistryError;
+
+fn registry(names: &[&str]) -> BiomeRegistry {
+    let mut registry = BiomeRegistry::new();
+    for name in names {
+        registry.register(name).unwrap();
+    }
+    registry
+}
+
+#[test]
+fn install() {
+    let first = registry(&["desert", "forest"]).install();
+    assert!(std::ptr::eq(BiomeRegistry::installed().unwrap(), first));
+    assert_eq!(BiomeId::variants(), &[BiomeId(0), BiomeId(1)]);
+
+    // same biomes: the installed registry is reused, nothing new is leaked
+    let again = registry(&["desert", "forest"]).install();
+    assert!(std::ptr::eq(first, again));
+    let names = BiomeRegistry::install_names(&["desert", "forest"]).unwrap();
+    assert!(std::ptr::eq(first, names));
+    assert_eq!(
+        BiomeRegistry::install_names(&["snow"]),
+        Err(RegistryError::Installed(vec![
+            "desert".into(),
+            "forest".into()
+        ]))
+    );
+
+    // different biomes replace it
+    let other = registry(&["snow", "desert", "forest"]).install();
+    assert!(!std::ptr::eq(first, other));
+    assert!(std::ptr::eq(BiomeRegistry::installed().unwrap(), other));
+    assert_eq!(BiomeId::variants().len(), 3);
+
+    let worley: Worley<BiomeId, SimpleBiomePicker<BiomeId>> = Worley::default();
+    assert_eq!(worley.validate(), Ok(()));
+}
@@ -0,0 +1,182 @@
+//! ScatterLayer placements don't depend on how the world is split into chunks
+
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest]
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Item {
+    Tree,
+    Rock,
+}
+
+fn worley() -> Worley<Biome, SimpleBiomePicker<Biome>> {
+    Worley::builder()
+        .seed(3)
+        .zoom(40.0)
+        .sharpness(2.0)
+        .build()
+        .expect("valid worley")
+}
+
+fn density(biome: &Biome, item: &Item) -> f64 {
+    match (biome, item) {
+        (Biome::Forest, Item::Tree) => 0.8,
+        (Biome::Desert, Item::Rock) => 0.3,
+        _ => 0.05,
+    }
+}
+
+fn sorted(mut placements: Vec<Placement<Item>>) -> Vec<Placement<Item>> {
+    placements.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.z.total_cmp(&b.z)));
+    placements
+}
+
+#[test]
+fn adjacent_chunks_match_one_region() {
+    let worley = worley();
+    let patterns = [
+        ScatterPattern::JitteredGrid {
+            spacing: 3.0,
+            jitter: 1.0,
+        },
+        ScatterPattern::JitteredGrid {
+            spacing: 2.5,
+            jitter: 0.3,
+        },
+        ScatterPattern::PoissonDisk { radius: 4.0 },
+    ];
+    for pattern in patterns {
+        let layer = ScatterLayer::new(pattern, 9, vec![Item::Tree, Item::Rock]).unwrap();
+        let mut chunks = layer.scatter(&worley, Region::chunk(-1, 2, 32.0), density);
+        chunks.extend(layer.scatter(&worley, Region::chunk(0, 2, 32.0), density));
+        let whole = layer.scatter(&worley, Region::new((-32.0, 64.0), (32.0, 96.0)), density);
+        assert!(!whole.is_empty());
+        assert_eq!(sorted(chunks), sorted(whole), "{pattern:?}");
+    }
+}
+
+#[test]
+fn poisson_disk_keeps_its_radius() {
+    let layer = ScatterLayer::new(
+        ScatterPattern::PoissonDisk { radius: 4.0 },
+        1,
+        vec![Item::Tree],
+    )
+    .unwrap();
+    let placements = layer.scatter(&worley(), Region::new((0.0, 0.0), (64.0, 64.0)), |_, _| 1.0);
+    for (i, a) in placements.iter().enumerate() {
+        for b in &placements[i + 1..] {
+            let distance = ((a.x - b.x).powi(2) + (a.z - b.z).powi(2)).sqrt();
+            assert!(distance >= 4.0, "{a:?} {b:?}");
+        }
+    }
+}
+
+#[test]
+fn invalid_patterns_are_rejected() {
+    let invalid = [
+        (
+            ScatterPattern::JitteredGrid {
+                spacing: 0.0,
+                jitter: 0.5,
+            },
+            WorleyError::InvalidScatterSpacing(0.0),
+        ),
+        (
+            ScatterPattern::JitteredGrid {
+                spacing: -2.0,
+                jitter: 0.5,
+            },
+            WorleyError::InvalidScatterSpacing(-2.0),
+        ),
+        (
+            ScatterPattern::JitteredGrid {
+                spacing: f64::INFINITY,
+                jitter: 0.5,
+            },
+            WorleyError::InvalidScatterSpacing(f64::INFINITY),
+        ),
+        (
+            ScatterPattern::JitteredGrid {
+                spacing: 2.0,
+                jitter: 1.5,
+            },
+            WorleyError::InvalidScatterJitter(1.5),
+        ),
+        (
+            ScatterPattern::PoissonDisk { radius: 0.0 },
+            WorleyError::InvalidScatterRadius(0.0),
+        ),
+    ];
+    for (pattern, err) in invalid {
+        assert_eq!(pattern.validate(), Err(err.clone()));
+        assert_eq!(ScatterLayer::new(pattern, 0, vec![Item::Tree]), Err(err));
+    }
+    assert!(matches!(
+        ScatterPattern::PoissonDisk { radius: f64::NAN }.validate(),
+        Err(WorleyError::InvalidScatterRadius(_))
+    ));
+}
+
+#[test]
+fn invalid_layers_place_nothing() {
+    let worley = worley();
+    let region = Region::new((0.0, 0.0), (64.0, 64.0));
+    // fields are public, a layer can skip ScatterLayer::new()
+    let layer = ScatterLayer {
+        pattern: ScatterPattern::PoissonDisk { radius: f64::NAN },
+        seed: 0,
+        items: vec![Item::Tree],
+    };
+    assert!(layer.scatter(&worley, region, |_, _| 1.0).is_empty());
+    let layer = ScatterLayer {
+        pattern: ScatterPattern::JitteredGrid {
+            spacing: 1.0,
+            jitter: 0.5,
+        },
+        seed: 0,
+        items: vec![Item::Tree],
+    };
+    let endless = Region::new((0.0, 0.0), (f64::INFINITY, 1.0));
+    assert!(layer.scatter(&worley, endless, |_, _| 1.0).is_empty());
+}
+
+#[test]
+fn layer_seed_is_independent_of_worley() {
+    let world = WorldSeed::from("mossy hills");
+    let trees = world.decoration("trees");
+    assert_ne!(trees, world.decoration("rocks"));
+    assert_eq!(
+        trees,
+        WorldSeed(world.layer(SeedLayer::Decoration))
+            .derive("trees")
+            .0
+    );
+
+    let pattern = ScatterPattern::JitteredGrid {
+        spacing: 3.0,
+        jitter: 1.0,
+    };
+    let layer = ScatterLayer::new(pattern, trees, vec![Item::Tree]).unwrap();
+    let region = Region::new((0.0, 0.0), (64.0, 64.0));
+    let mut worley = worley();
+    let before = layer.scatter(&worley, region, |_, _| 1.0);
+    // rerolling the biomes keeps every candidate where it was
+    worley.set_world_seed(WorldSeed::from("dry plains"));
+    assert_eq!(layer.scatter(&worley, region, |_, _| 1.0), before);
+
+    let rerolled = ScatterLayer::new(pattern, world.decoration("rocks"), vec![Item::Tree]).unwrap();
+    assert_ne!(rerolled.scatter(&worley, region, |_, _| 1.0), before);
+}
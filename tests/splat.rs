@@ -0,0 +1,98 @@
+//! splat maps hold the same weights as the sample grid they were made from
+
+use worley_biomes::prelude::*;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+    Plains,
+    Swamp,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[
+            Self::Desert,
+            Self::Forest,
+            Self::Snow,
+            Self::Plains,
+            Self::Swamp,
+        ]
+    }
+}
+
+fn grid() -> worley_biomes::region::SampleGrid<Biome> {
+    let worley: Worley<Biome, SimpleBiomePicker<Biome>> = Worley::builder()
+        .seed(9)
+        .zoom(8.0)
+        .sharpness(4.0)
+        .build()
+        .unwrap();
+    worley.sample_region(Region::new((0.0, 0.0), (64.0, 32.0)), 32, 16)
+}
+
+fn index(biome: Biome) -> usize {
+    Biome::variants().iter().position(|b| *b == biome).unwrap()
+}
+
+#[test]
+fn channels_hold_every_biome() {
+    let grid = grid();
+    let splat = SplatMap::new(&grid, SplatFormat::Channels);
+    // 5 biomes: RGBA + R
+    assert_eq!(splat.layers.len(), 2);
+    assert!(splat.layers.iter().all(|layer| layer.len() == 32 * 16 * 4));
+    assert_eq!(splat.array_data().len(), 2 * 32 * 16 * 4);
+    for z in 0..16 {
+        for x in 0..32 {
+            let mut expected = [0u8; 8];
+            for (weight, biome) in grid.get(x, z) {
+                expected[index(*biome)] = (weight * 255.0).round() as u8;
+            }
+            let [a, b] = [splat.texel(0, x, z), splat.texel(1, x, z)];
+            assert_eq!([a, b].concat(), expected);
+        }
+    }
+}
+
+#[test]
+fn index_weight_keeps_two_strongest() {
+    let grid = grid();
+    let splat = SplatMap::new(&grid, SplatFormat::IndexWeight);
+    assert_eq!(splat.layers.len(), 1);
+    let mut blended = 0;
+    for z in 0..16 {
+        for x in 0..32 {
+            let mut weights = grid.get(x, z).to_vec();
+            weights.sort_by(|a, b| b.0.total_cmp(&a.0));
+            let [first, second, weight_a, weight_b] = splat.texel(0, x, z);
+            assert_eq!(first as usize, index(weights[0].1));
+            assert_eq!(weight_a as u16 + weight_b as u16, 255);
+            assert!(weight_a >= weight_b);
+            if weights.len() > 1 {
+                assert_eq!(second as usize, index(weights[1].1));
+                blended += 1;
+            } else {
+                assert_eq!((second, weight_a), (first, 255));
+            }
+        }
+    }
+    assert!(blended > 0);
+}
+
+#[cfg(feature = "bevy")]
+#[test]
+fn bevy_images() {
+    use worley_biomes::bevy::splat_image::{splat_array_image, splat_image};
+
+    let splat = SplatMap::new(&grid(), SplatFormat::Channels);
+    let image = splat_image(&splat, 1);
+    assert_eq!((image.width(), image.height()), (32, 16));
+    assert_eq!(image.data.as_deref(), Some(splat.layers[1].as_slice()));
+    let array = splat_array_image(&splat);
+    assert_eq!(array.texture_descriptor.size.depth_or_array_layers, 2);
+    assert_eq!(array.data, Some(splat.array_data()));
+}
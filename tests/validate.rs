@@ -0,0 +1,233 @@
+//! Worley::validate() rejects every config that would panic or produce garbage when sampled
+
+use worley_biomes::prelude::*;
+use worley_biomes::worley::MAX_K;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Biome {
+    #[default]
+    Desert,
+    Forest,
+    Snow,
+}
+
+impl BiomeVariants for Biome {
+    fn variants() -> &'static [Self] {
+        &[Self::Desert, Self::Forest, Self::Snow]
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+struct Nothing;
+
+impl BiomeVariants for Nothing {
+    fn variants() -> &'static [Self] {
+        &[]
+    }
+}
+
+fn builder() -> WorleyBuilder<Biome, SimpleBiomePicker<Biome>> {
+    Worley::builder().seed(3).zoom(10.0).warp_strength(0.0)
+}
+
+fn error(builder: WorleyBuilder<Biome, SimpleBiomePicker<Biome>>) -> WorleyError {
+    builder.build().err().expect("invalid worley")
+}
+
+#[test]
+fn picker_errors() {
+    let any = Worley::<Nothing, SimpleBiomePicker<Nothing>>::builder().build();
+    assert_eq!(any.err(), Some(WorleyError::NoVariants));
+
+    let any_of = builder().biome_picker(SimpleBiomePicker::AnyOf(vec![]));
+    assert_eq!(error(any_of), WorleyError::EmptyAnyOf);
+
+    let weighted = builder().biome_picker(SimpleBiomePicker::Weighted(vec![]));
+    assert_eq!(error(weighted), WorleyError::EmptyWeights);
+
+    for weight in [-0.5, f32::NAN, f32::INFINITY] {
+        let weighted = builder().biome_picker(SimpleBiomePicker::Weighted(vec![
+            (Biome::Desert, 0.5),
+            (Biome::Forest, weight),
+        ]));
+        assert!(matches!(
+            error(weighted),
+            WorleyError::InvalidWeight { index: 1, .. }
+        ));
+    }
+
+    let weighted = builder().biome_picker(SimpleBiomePicker::Weighted(vec![
+        (Biome::Desert, 0.5),
+        (Biome::Forest, 0.2),
+    ]));
+    assert!(matches!(error(weighted), WorleyError::WeightSum(_)));
+}
+
+#[test]
+fn setting_errors() {
+    assert_eq!(error(builder().k(0)), WorleyError::InvalidK(0));
+    assert_eq!(
+        error(builder().k(MAX_K + 1)),
+        WorleyError::InvalidK(MAX_K + 1)
+    );
+
+    for zoom in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert!(matches!(
+            error(builder().zoom(zoom)),
+            WorleyError::InvalidZoom(_)
+        ));
+    }
+
+    for sharpness in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        assert!(matches!(
+            error(builder().sharpness(sharpness)),
+            WorleyError::InvalidSharpness(_)
+        ));
+    }
+
+    for threshold in [-0.01, 1.0, 1.5, f64::NAN] {
+        assert!(matches!(
+            error(builder().kill_percent_threshold(Some(threshold))),
+            WorleyError::InvalidKillThreshold(_)
+        ));
+    }
+
+    for strength in [f32::NAN, f32::INFINITY] {
+        assert!(matches!(
+            error(builder().warp_strength(strength)),
+            WorleyError::InvalidWarpStrength(_)
+        ));
+    }
+}
+
+#[test]
+fn scatter_errors() {
+    let grid = |spacing, jitter| ScatterPattern::JitteredGrid { spacing, jitter };
+    assert!(matches!(
+        grid(0.0, 0.5).validate(),
+        Err(WorleyError::InvalidScatterSpacing(_))
+    ));
+    assert!(matches!(
+        grid(4.0, 1.5).validate(),
+        Err(WorleyError::InvalidScatterJitter(_))
+    ));
+    assert!(matches!(
+        ScatterPattern::PoissonDisk { radius: -1.0 }.validate(),
+        Err(WorleyError::InvalidScatterRadius(_))
+    ));
+}
+
+#[test]
+fn errors_display() {
+    let errors = [
+        WorleyError::NoVariants,
+        WorleyError::EmptyAnyOf,
+        WorleyError::EmptyWeights,
+        WorleyError::InvalidWeight {
+            index: 2,
+            weight: -1.0,
+        },
+        WorleyError::WeightSum(0.7),
+        WorleyError::InvalidK(0),
+        WorleyError::InvalidZoom(0.0),
+        WorleyError::InvalidSharpness(f64::NAN),
+        WorleyError::InvalidKillThreshold(1.0),
+        WorleyError::InvalidWarpStrength(f32::NAN),
+        WorleyError::InvalidScatterSpacing(0.0),
+        WorleyError::InvalidScatterJitter(2.0),
+        WorleyError::InvalidScatterRadius(0.0),
+        WorleyError::InvalidChunkSize(0.0),
+        WorleyError::InvalidChunkDistance { load: 4, unload: 2 },
+        WorleyError::UnsupportedNoiseSetting {
+            setting: "noise_type",
+            kind: worley_biomes::noise::NoiseKind::Simplex,
+        },
+    ];
+    for error in errors {
+        assert!(!error.to_string().is_empty(), "{error:?}");
+    }
+}
+
+#[test]
+fn max_k_samples_every_neighbor() {
+    for algorithm in AlgorithmVersion::ALL {
+        let worley = builder()
+            .k(MAX_K)
+            .kill_percent_threshold(None)
+            .algorithm(algorithm)
+            .build()
+            .expect("k == MAX_K is valid");
+        for i in 0..200 {
+            let (x, z) = (i as f64 * 7.3 - 500.0, i as f64 * -3.1 + 80.0);
+            let weights = worley.get(x, z);
+            assert!(weights.len() <= MAX_K, "{algorithm:?} at {x}, {z}");
+            let sum: f64 = weights.iter().map(|(weight, _)| weight).sum();
+            assert!((sum - 1.0).abs() < 1e-9, "{algorithm:?} at {x}, {z}");
+        }
+    }
+}
+
+#[test]
+fn kill_threshold_keeps_strongest() {
+    // every share of 9 neighbors is below 0.99, the strongest has to survive anyway
+    for algorithm in AlgorithmVersion::ALL {
+        let worley = builder()
+            .k(MAX_K)
+            .sharpness(0.5)
+            .kill_percent_threshold(Some(0.99))
+            .algorithm(algorithm)
+            .build()
+            .expect("valid worley");
+        for i in 0..200 {
+            let (x, z) = (i as f64 * 7.3 - 500.0, i as f64 * -3.1 + 80.0);
+            let weights = worley.get(x, z);
+            assert!(!weights.is_empty(), "{algorithm:?} at {x}, {z}");
+            let sum: f64 = weights.iter().map(|(weight, _)| weight).sum();
+            assert!((sum - 1.0).abs() < 1e-9, "{algorithm:?} at {x}, {z}");
+            assert_eq!(worley.inspect(x, z).weights, weights);
+        }
+    }
+}
+
+// FastNoise only settings don't switch the default Simplex noise to FastNoise
+#[cfg(feature = "fast-noise")]
+#[test]
+fn fast_noise_settings() {
+    use bracket_fast_noise::prelude::{FastNoise, FractalType, NoiseType};
+    use worley_biomes::noise::NoiseKind;
+
+    assert!(matches!(Noise::default(), Noise::Simplex(_)));
+    assert_eq!(
+        error(builder().noise_type(NoiseType::Perlin)),
+        WorleyError::UnsupportedNoiseSetting {
+            setting: "noise_type",
+            kind: NoiseKind::Simplex,
+        }
+    );
+    assert_eq!(
+        error(
+            builder()
+                .warp_noise(ValueNoise::default())
+                .fractal_type(FractalType::Billow)
+                .noise_type(NoiseType::Perlin)
+        ),
+        WorleyError::UnsupportedNoiseSetting {
+            setting: "fractal_type",
+            kind: NoiseKind::Value,
+        }
+    );
+
+    let worley = builder()
+        .warp_noise(FastNoise::default())
+        .noise_type(NoiseType::PerlinFractal)
+        .fractal_type(FractalType::FBM)
+        .build()
+        .expect("valid worley");
+    match &worley.warp_settings.noise {
+        Noise::FastNoise(noise) => {
+            assert_eq!(noise.noise_type, NoiseType::PerlinFractal);
+            assert_eq!(noise.fractal_type, FractalType::FBM);
+        }
+        noise => panic!("expected FastNoise, got {:?}", noise.kind()),
+    }
+}